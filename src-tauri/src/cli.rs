@@ -0,0 +1,76 @@
+use crate::models::GraphQuery;
+use crate::services::{graph, indexer};
+
+const GRAPH_USAGE: &str = "Usage: velocitext export-graph [--format json|dot] [--tag TAG] \
+[--folder FOLDER] [--around NOTE] [--depth N] [--no-tags] [--no-unresolved]";
+
+// Returns the process exit code when the arguments name a CLI command, or
+// None when the desktop app should start as usual.
+pub fn run(args: &[String]) -> Option<i32> {
+  match args.first().map(String::as_str) {
+    Some("export-graph") => Some(export_graph(&args[1..])),
+    _ => None,
+  }
+}
+
+fn export_graph(args: &[String]) -> i32 {
+  let mut query = GraphQuery::default();
+  let mut format = "json".to_string();
+  let mut args = args.iter();
+
+  while let Some(arg) = args.next() {
+    let result = match arg.as_str() {
+      "--format" => next_value(&mut args, arg).map(|value| format = value),
+      "--tag" => next_value(&mut args, arg).map(|value| query.tag = Some(value)),
+      "--folder" => next_value(&mut args, arg).map(|value| query.folder = Some(value)),
+      "--around" => next_value(&mut args, arg).map(|value| query.around = Some(value)),
+      "--depth" => next_value(&mut args, arg).and_then(|value| {
+        value
+          .parse()
+          .map(|depth| query.depth = depth)
+          .map_err(|_| format!("Invalid depth: {}", value))
+      }),
+      "--no-tags" => {
+        query.include_tags = false;
+        Ok(())
+      }
+      "--no-unresolved" => {
+        query.include_unresolved = false;
+        Ok(())
+      }
+      other => Err(format!("Unknown argument: {}", other)),
+    };
+
+    if let Err(error) = result {
+      eprintln!("{}\n{}", error, GRAPH_USAGE);
+      return 2;
+    }
+  }
+
+  // The app may not have run since the notes last changed.
+  if let Err(error) = indexer::rebuild_index() {
+    eprintln!("Index rebuild failed: {}", error);
+    return 1;
+  }
+
+  match graph::export(&query, &format) {
+    Ok(output) => {
+      print!("{}", output);
+      0
+    }
+    Err(error) => {
+      eprintln!("Graph export failed: {}", error);
+      1
+    }
+  }
+}
+
+fn next_value<'a>(
+  args: &mut impl Iterator<Item = &'a String>,
+  flag: &str,
+) -> Result<String, String> {
+  args
+    .next()
+    .cloned()
+    .ok_or_else(|| format!("Missing value for {}", flag))
+}
@@ -0,0 +1,38 @@
+use crate::models::{AppError, DailyNoteDay, DailyNoteSettings, NoteDetail, NoteMetadata};
+use crate::services::{daily_notes, indexer};
+
+#[tauri::command]
+pub fn open_daily_note(date: Option<String>) -> Result<NoteDetail, AppError> {
+  let detail = daily_notes::open_daily_note(date.as_deref())?;
+  if let Err(error) = indexer::reindex_note(&detail.metadata.id) {
+    eprintln!("Index update failed: {}", error);
+  }
+  Ok(detail)
+}
+
+#[tauri::command]
+pub fn previous_daily_note(date: String) -> Result<Option<NoteMetadata>, AppError> {
+  daily_notes::adjacent_daily_note(&date, false)
+}
+
+#[tauri::command]
+pub fn next_daily_note(date: String) -> Result<Option<NoteMetadata>, AppError> {
+  daily_notes::adjacent_daily_note(&date, true)
+}
+
+#[tauri::command]
+pub fn daily_note_calendar(year: i32, month: u32) -> Result<Vec<DailyNoteDay>, AppError> {
+  daily_notes::daily_note_calendar(year, month)
+}
+
+#[tauri::command]
+pub fn get_daily_note_settings() -> Result<DailyNoteSettings, AppError> {
+  daily_notes::get_daily_note_settings()
+}
+
+#[tauri::command]
+pub fn save_daily_note_settings(
+  settings: DailyNoteSettings,
+) -> Result<DailyNoteSettings, AppError> {
+  daily_notes::save_daily_note_settings(&settings)
+}
@@ -0,0 +1,12 @@
+use crate::models::{AppError, DateSettings};
+use crate::services::dates;
+
+#[tauri::command]
+pub fn get_date_settings() -> Result<DateSettings, AppError> {
+  dates::get_date_settings()
+}
+
+#[tauri::command]
+pub fn save_date_settings(settings: DateSettings) -> Result<DateSettings, AppError> {
+  dates::save_date_settings(&settings)
+}
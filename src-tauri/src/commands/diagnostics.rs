@@ -0,0 +1,24 @@
+use crate::commands::notes::refresh_index;
+use crate::models::{AppError, RepairReport, SavedNote};
+use crate::services::{diagnostics, indexer};
+
+#[tauri::command]
+pub fn apply_fix(note_id: String, fix_id: String) -> Result<SavedNote, AppError> {
+  let id = indexer::resolve_note_id(&note_id)?;
+  let detail = diagnostics::apply_fix(&id, &fix_id)?;
+  let relinked = refresh_index(&id, &detail);
+  Ok(SavedNote { detail, relinked })
+}
+
+#[tauri::command]
+pub fn repair_vault(dry_run: bool) -> Result<RepairReport, AppError> {
+  let report = diagnostics::repair_vault(dry_run)?;
+  if !dry_run {
+    for note in report.notes.iter().filter(|note| note.rewritten) {
+      if let Err(error) = indexer::reindex_note(&note.id) {
+        eprintln!("Index update failed: {}", error);
+      }
+    }
+  }
+  Ok(report)
+}
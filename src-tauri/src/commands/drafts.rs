@@ -0,0 +1,37 @@
+use crate::commands::notes::refresh_index;
+use crate::models::{AppError, NoteDraft, SavedNote};
+use crate::services::{drafts, history, indexer, notes_fs};
+
+#[tauri::command]
+pub fn stash_draft(id: String, content: String) -> Result<NoteDraft, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  drafts::stash_draft(&id, &content)
+}
+
+#[tauri::command]
+pub fn list_recoverable_drafts() -> Result<Vec<NoteDraft>, AppError> {
+  drafts::list_recoverable_drafts()
+}
+
+#[tauri::command]
+pub fn discard_draft(id: String) -> Result<(), AppError> {
+  // The draft of a note deleted since can still be discarded by file name.
+  let id = indexer::resolve_note_id(&id).unwrap_or(id);
+  drafts::discard_draft(&id)
+}
+
+// Saves the draft over the note; the draft is kept when the save fails.
+#[tauri::command]
+pub fn apply_draft(id: String) -> Result<SavedNote, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  let draft = drafts::get_draft(&id)?;
+  if let Err(error) = history::snapshot_note(&id, true) {
+    eprintln!("History snapshot failed: {}", error);
+  }
+  let detail = notes_fs::save_note(&id, &draft.content)?;
+  if let Err(error) = drafts::discard_draft(&id) {
+    eprintln!("Draft cleanup failed: {}", error);
+  }
+  let relinked = refresh_index(&id, &detail);
+  Ok(SavedNote { detail, relinked })
+}
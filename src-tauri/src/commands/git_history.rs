@@ -0,0 +1,39 @@
+use crate::commands::notes::refresh_index;
+use crate::models::{AppError, NoteCommit, SavedNote};
+use crate::services::{git_history, indexer};
+
+#[tauri::command]
+pub fn get_git_history_enabled() -> Result<bool, AppError> {
+  git_history::is_enabled()
+}
+
+#[tauri::command]
+pub fn set_git_history_enabled(enabled: bool) -> Result<bool, AppError> {
+  git_history::set_enabled(enabled)
+}
+
+#[tauri::command]
+pub fn list_note_commits(id: String) -> Result<Vec<NoteCommit>, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  git_history::note_log(&id)
+}
+
+#[tauri::command]
+pub fn get_note_at_commit(id: String, commit: String) -> Result<String, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  git_history::get_note_at(&id, &commit)
+}
+
+#[tauri::command]
+pub fn diff_note_commits(id: String, from: String, to: Option<String>) -> Result<String, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  git_history::diff_note_commits(&id, &from, to.as_deref())
+}
+
+#[tauri::command]
+pub fn restore_note_commit(id: String, commit: String) -> Result<SavedNote, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  let detail = git_history::restore_note_commit(&id, &commit)?;
+  let relinked = refresh_index(&id, &detail);
+  Ok(SavedNote { detail, relinked })
+}
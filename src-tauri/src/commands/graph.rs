@@ -0,0 +1,12 @@
+use crate::models::{AppError, GraphQuery, NoteGraph};
+use crate::services::graph;
+
+#[tauri::command]
+pub fn note_graph(query: GraphQuery) -> Result<NoteGraph, AppError> {
+  graph::note_graph(&query)
+}
+
+#[tauri::command]
+pub fn export_note_graph(query: GraphQuery, format: String) -> Result<String, AppError> {
+  graph::export(&query, &format)
+}
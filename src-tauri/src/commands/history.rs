@@ -0,0 +1,29 @@
+use crate::commands::notes::refresh_index;
+use crate::models::{AppError, NoteVersion, SavedNote};
+use crate::services::{history, indexer};
+
+#[tauri::command]
+pub fn list_note_versions(id: String) -> Result<Vec<NoteVersion>, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  history::list_versions(&id)
+}
+
+#[tauri::command]
+pub fn get_note_version(id: String, version: String) -> Result<String, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  history::get_version(&id, &version)
+}
+
+#[tauri::command]
+pub fn diff_note_version(id: String, version: String) -> Result<String, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  history::diff_version(&id, &version)
+}
+
+#[tauri::command]
+pub fn restore_note_version(id: String, version: String) -> Result<SavedNote, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  let detail = history::restore_version(&id, &version)?;
+  let relinked = refresh_index(&id, &detail);
+  Ok(SavedNote { detail, relinked })
+}
@@ -1,6 +1,7 @@
+use crate::models::AppError;
 use crate::services::indexer;
 
 #[tauri::command]
-pub fn rebuild_index() -> Result<(), String> {
-  indexer::rebuild_index().map_err(|error| error.to_string())
+pub fn rebuild_index() -> Result<(), AppError> {
+  indexer::rebuild_index()
 }
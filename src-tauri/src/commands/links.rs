@@ -0,0 +1,14 @@
+use crate::models::{AppError, NoteLink};
+use crate::services::{indexer, links};
+
+#[tauri::command]
+pub fn get_backlinks(id: String) -> Result<Vec<NoteLink>, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  links::get_backlinks(&id)
+}
+
+#[tauri::command]
+pub fn get_outgoing_links(id: String) -> Result<Vec<NoteLink>, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  links::get_outgoing_links(&id)
+}
@@ -1,3 +1,18 @@
+pub mod daily;
+pub mod dates;
+pub mod diagnostics;
+pub mod drafts;
+pub mod git_history;
+pub mod graph;
+pub mod history;
 pub mod indexer;
+pub mod links;
 pub mod notes;
+pub mod outline;
+pub mod saved_searches;
 pub mod search;
+pub mod stversions;
+pub mod tasks;
+pub mod templates;
+pub mod todo;
+pub mod trash;
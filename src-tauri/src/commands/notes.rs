@@ -1,27 +1,69 @@
-use crate::models::{NoteDetail, NoteMetadata};
-use crate::services::notes_fs;
+use crate::models::{AppError, ListQuery, NoteDetail, NotePage, SavedNote};
+use crate::services::{drafts, history, indexer, links, notes_fs, search};
 
 #[tauri::command]
-pub fn list_notes() -> Result<Vec<NoteMetadata>, String> {
-  notes_fs::list_notes().map_err(|error| error.to_string())
+pub fn list_notes(query: ListQuery) -> Result<NotePage, AppError> {
+  search::list_notes(&query)
 }
 
 #[tauri::command]
-pub fn get_note(id: String) -> Result<NoteDetail, String> {
-  notes_fs::get_note(&id).map_err(|error| error.to_string())
+pub fn get_note(id: String) -> Result<NoteDetail, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  notes_fs::get_note(&id)
 }
 
 #[tauri::command]
-pub fn create_note(title: String) -> Result<NoteDetail, String> {
-  notes_fs::create_note(&title).map_err(|error| error.to_string())
+pub fn create_note(title: String) -> Result<NoteDetail, AppError> {
+  let detail = notes_fs::create_note(&title)?;
+  refresh_index(&detail.metadata.id, &detail);
+  Ok(detail)
 }
 
 #[tauri::command]
-pub fn save_note(id: String, content: String) -> Result<NoteDetail, String> {
-  notes_fs::save_note(&id, &content).map_err(|error| error.to_string())
+pub fn save_note(id: String, content: String) -> Result<SavedNote, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  if let Err(error) = history::snapshot_note(&id, false) {
+    eprintln!("History snapshot failed: {}", error);
+  }
+  let detail = notes_fs::save_note(&id, &content)?;
+  // The editor's stashed buffer is on disk now.
+  if let Err(error) = drafts::discard_draft(&id) {
+    eprintln!("Draft cleanup failed: {}", error);
+  }
+  let relinked = refresh_index(&id, &detail);
+  Ok(SavedNote { detail, relinked })
 }
 
 #[tauri::command]
-pub fn archive_note(id: String, archived: bool) -> Result<NoteDetail, String> {
-  notes_fs::set_archived(&id, archived).map_err(|error| error.to_string())
+pub fn archive_note(id: String, archived: bool) -> Result<NoteDetail, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  let detail = notes_fs::set_archived(&id, archived)?;
+  refresh_index(&id, &detail);
+  Ok(detail)
+}
+
+// Lists are served from the index, so writes update it right away instead of
+// waiting for the watcher to pick up the file change. When the note was
+// renamed, links pointing at the old file are rewritten before the old entry
+// leaves the index; the rewritten note ids are returned.
+pub fn refresh_index(previous_id: &str, detail: &NoteDetail) -> Vec<String> {
+  if let Err(error) = indexer::reindex_note(&detail.metadata.id) {
+    eprintln!("Index update failed: {}", error);
+  }
+
+  if previous_id == detail.metadata.id {
+    return Vec::new();
+  }
+
+  let relinked =
+    links::relink_renamed_note(previous_id, &detail.metadata.id).unwrap_or_else(|error| {
+      eprintln!("Relink failed: {}", error);
+      Vec::new()
+    });
+
+  if let Err(error) = indexer::remove_note(previous_id) {
+    eprintln!("Index update failed: {}", error);
+  }
+
+  relinked
 }
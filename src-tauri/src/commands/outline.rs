@@ -0,0 +1,8 @@
+use crate::models::{AppError, NoteSection};
+use crate::services::{indexer, outline};
+
+#[tauri::command]
+pub fn get_outline(id: String) -> Result<Vec<NoteSection>, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  outline::get_outline(&id)
+}
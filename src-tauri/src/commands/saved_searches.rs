@@ -0,0 +1,31 @@
+use crate::models::{AppError, NoteMetadata, SavedSearch, SavedSearchCount};
+use crate::services::saved_searches;
+
+#[tauri::command]
+pub fn list_saved_searches() -> Result<Vec<SavedSearchCount>, AppError> {
+  saved_searches::list_saved_searches()
+}
+
+#[tauri::command]
+pub fn run_saved_search(id: String) -> Result<Vec<NoteMetadata>, AppError> {
+  saved_searches::run_saved_search(&id)
+}
+
+#[tauri::command]
+pub fn create_saved_search(name: String, query: String) -> Result<SavedSearch, AppError> {
+  saved_searches::create_saved_search(&name, &query)
+}
+
+#[tauri::command]
+pub fn update_saved_search(
+  id: String,
+  name: String,
+  query: String,
+) -> Result<SavedSearch, AppError> {
+  saved_searches::update_saved_search(&id, &name, &query)
+}
+
+#[tauri::command]
+pub fn delete_saved_search(id: String) -> Result<(), AppError> {
+  saved_searches::delete_saved_search(&id)
+}
@@ -1,7 +1,7 @@
-use crate::models::NoteMetadata;
+use crate::models::{AppError, SearchHit, SearchRanking};
 use crate::services::search;
 
 #[tauri::command]
-pub fn search_notes(query: String) -> Result<Vec<NoteMetadata>, String> {
-  search::search_notes(&query).map_err(|error| error.to_string())
+pub fn search_notes(query: String, ranking: Option<SearchRanking>) -> Result<Vec<SearchHit>, AppError> {
+  search::search_notes(&query, &ranking.unwrap_or_default())
 }
@@ -0,0 +1,29 @@
+use crate::commands::notes::refresh_index;
+use crate::models::{AppError, NoteVersion, SavedNote};
+use crate::services::{indexer, stversions};
+
+#[tauri::command]
+pub fn list_syncthing_versions(id: String) -> Result<Vec<NoteVersion>, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  stversions::list_versions(&id)
+}
+
+#[tauri::command]
+pub fn get_syncthing_version(id: String, version: String) -> Result<String, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  stversions::get_version(&id, &version)
+}
+
+#[tauri::command]
+pub fn diff_syncthing_version(id: String, version: String) -> Result<String, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  stversions::diff_version(&id, &version)
+}
+
+#[tauri::command]
+pub fn restore_syncthing_version(id: String, version: String) -> Result<SavedNote, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  let detail = stversions::restore_version(&id, &version)?;
+  let relinked = refresh_index(&id, &detail);
+  Ok(SavedNote { detail, relinked })
+}
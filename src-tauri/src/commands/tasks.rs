@@ -0,0 +1,20 @@
+use crate::models::{AppError, NoteDetail, NoteTask, TaskQuery};
+use crate::services::{indexer, tasks};
+
+#[tauri::command]
+pub fn list_tasks(mut query: TaskQuery) -> Result<Vec<NoteTask>, AppError> {
+  if let Some(note_id) = &query.note_id {
+    query.note_id = Some(indexer::resolve_note_id(note_id)?);
+  }
+  tasks::list_tasks(&query)
+}
+
+#[tauri::command]
+pub fn toggle_task(note_id: String, line: usize, text: String) -> Result<NoteDetail, AppError> {
+  let note_id = indexer::resolve_note_id(&note_id)?;
+  let detail = tasks::toggle_task(&note_id, line, &text)?;
+  if let Err(error) = indexer::reindex_note(&detail.metadata.id) {
+    eprintln!("Index update failed: {}", error);
+  }
+  Ok(detail)
+}
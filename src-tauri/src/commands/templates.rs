@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+use crate::models::{AppError, NoteTemplate, TemplateNote};
+use crate::services::{indexer, templates};
+
+#[tauri::command]
+pub fn list_templates() -> Result<Vec<NoteTemplate>, AppError> {
+  templates::list_templates()
+}
+
+#[tauri::command]
+pub fn create_note_from_template(
+  template: String,
+  title: String,
+  values: Option<HashMap<String, String>>,
+) -> Result<TemplateNote, AppError> {
+  let note = templates::create_note_from_template(&template, &title, &values.unwrap_or_default())?;
+  if let Err(error) = indexer::reindex_note(&note.detail.metadata.id) {
+    eprintln!("Index update failed: {}", error);
+  }
+  Ok(note)
+}
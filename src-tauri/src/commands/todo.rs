@@ -0,0 +1,36 @@
+use crate::models::{AppError, TodoItem, TodoQuery};
+use crate::services::{indexer, todo_txt};
+
+#[tauri::command]
+pub fn list_todo_items(query: TodoQuery) -> Result<Vec<TodoItem>, AppError> {
+  todo_txt::list_todo_items(&query)
+}
+
+#[tauri::command]
+pub fn add_todo_item(text: String) -> Result<TodoItem, AppError> {
+  let item = todo_txt::add_todo_item(&text)?;
+  refresh_todo_index(&[todo_txt::TODO_FILE]);
+  Ok(item)
+}
+
+#[tauri::command]
+pub fn complete_todo_item(line: usize, raw: String) -> Result<TodoItem, AppError> {
+  let item = todo_txt::complete_todo_item(line, &raw)?;
+  refresh_todo_index(&[todo_txt::TODO_FILE]);
+  Ok(item)
+}
+
+#[tauri::command]
+pub fn archive_todo_items() -> Result<usize, AppError> {
+  let moved = todo_txt::archive_done_items()?;
+  refresh_todo_index(&[todo_txt::TODO_FILE, todo_txt::DONE_FILE]);
+  Ok(moved)
+}
+
+fn refresh_todo_index(files: &[&str]) {
+  for file in files {
+    if let Err(error) = indexer::reindex_todo_file(file) {
+      eprintln!("Index update failed: {}", error);
+    }
+  }
+}
@@ -0,0 +1,46 @@
+use crate::models::{AppError, NoteDetail, TrashSettings, TrashedNote};
+use crate::services::{indexer, trash};
+
+#[tauri::command]
+pub fn delete_note(id: String) -> Result<TrashedNote, AppError> {
+  let id = indexer::resolve_note_id(&id)?;
+  let entry = trash::trash_note(&id)?;
+  if let Err(error) = indexer::remove_note(&id) {
+    eprintln!("Index update failed: {}", error);
+  }
+  Ok(entry)
+}
+
+#[tauri::command]
+pub fn list_trash() -> Result<Vec<TrashedNote>, AppError> {
+  trash::list_trash()
+}
+
+#[tauri::command]
+pub fn restore_from_trash(entry: String) -> Result<NoteDetail, AppError> {
+  let detail = trash::restore_from_trash(&entry)?;
+  if let Err(error) = indexer::reindex_note(&detail.metadata.id) {
+    eprintln!("Index update failed: {}", error);
+  }
+  Ok(detail)
+}
+
+#[tauri::command]
+pub fn delete_from_trash(entry: String) -> Result<(), AppError> {
+  trash::delete_from_trash(&entry)
+}
+
+#[tauri::command]
+pub fn empty_trash() -> Result<usize, AppError> {
+  trash::empty_trash()
+}
+
+#[tauri::command]
+pub fn get_trash_settings() -> Result<TrashSettings, AppError> {
+  trash::get_trash_settings()
+}
+
+#[tauri::command]
+pub fn save_trash_settings(settings: TrashSettings) -> Result<TrashSettings, AppError> {
+  trash::save_trash_settings(&settings)
+}
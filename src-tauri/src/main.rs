@@ -4,6 +4,7 @@ use std::sync::Mutex;
 
 use tauri::{CustomMenuItem, Manager, Menu, PhysicalPosition, PhysicalSize, Position, Size, Submenu};
 
+mod cli;
 mod commands;
 mod models;
 mod services;
@@ -17,6 +18,11 @@ impl Default for WatcherState {
 }
 
 fn main() {
+  let args = std::env::args().skip(1).collect::<Vec<_>>();
+  if let Some(code) = cli::run(&args) {
+    std::process::exit(code);
+  }
+
   let help_item = CustomMenuItem::new("help", "Help");
   let settings_item = CustomMenuItem::new("settings", "Settings");
   let help_menu = Submenu::new("Help", Menu::new().add_item(help_item));
@@ -40,11 +46,32 @@ fn main() {
         eprintln!("Index rebuild failed: {}", error);
       }
 
+      if let Err(error) = services::trash::purge_expired() {
+        eprintln!("Trash purge failed: {}", error);
+      }
+
       if let Err(error) = apply_window_layout(app) {
         eprintln!("Window layout failed: {}", error);
       }
 
-      match services::watcher::start_watching() {
+      let app_handle = app.handle();
+      let watcher = services::watcher::start_watching(move || {
+        match services::saved_searches::list_saved_searches() {
+          Ok(searches) => {
+            let _ = app_handle.emit_all("saved-searches://updated", searches);
+          }
+          Err(error) => {
+            eprintln!("Saved searches refresh failed: {}", error);
+          }
+        }
+
+        // One commit per watcher batch keeps autosave from flooding the log.
+        if let Err(error) = services::git_history::commit_all() {
+          eprintln!("Git commit failed: {}", error);
+        }
+      });
+
+      match watcher {
         Ok(handle) => {
           if let Ok(mut state) = app.state::<WatcherState>().0.lock() {
             *state = Some(handle);
@@ -63,8 +90,61 @@ fn main() {
       commands::notes::create_note,
       commands::notes::save_note,
       commands::notes::archive_note,
+      commands::daily::open_daily_note,
+      commands::daily::previous_daily_note,
+      commands::daily::next_daily_note,
+      commands::daily::daily_note_calendar,
+      commands::daily::get_daily_note_settings,
+      commands::daily::save_daily_note_settings,
+      commands::dates::get_date_settings,
+      commands::dates::save_date_settings,
+      commands::diagnostics::apply_fix,
+      commands::diagnostics::repair_vault,
+      commands::drafts::stash_draft,
+      commands::drafts::list_recoverable_drafts,
+      commands::drafts::discard_draft,
+      commands::drafts::apply_draft,
+      commands::git_history::get_git_history_enabled,
+      commands::git_history::set_git_history_enabled,
+      commands::git_history::list_note_commits,
+      commands::git_history::get_note_at_commit,
+      commands::git_history::diff_note_commits,
+      commands::git_history::restore_note_commit,
+      commands::graph::note_graph,
+      commands::graph::export_note_graph,
+      commands::history::list_note_versions,
+      commands::history::get_note_version,
+      commands::history::diff_note_version,
+      commands::history::restore_note_version,
       commands::indexer::rebuild_index,
-      commands::search::search_notes
+      commands::links::get_backlinks,
+      commands::links::get_outgoing_links,
+      commands::outline::get_outline,
+      commands::saved_searches::list_saved_searches,
+      commands::saved_searches::run_saved_search,
+      commands::saved_searches::create_saved_search,
+      commands::saved_searches::update_saved_search,
+      commands::saved_searches::delete_saved_search,
+      commands::search::search_notes,
+      commands::stversions::list_syncthing_versions,
+      commands::stversions::get_syncthing_version,
+      commands::stversions::diff_syncthing_version,
+      commands::stversions::restore_syncthing_version,
+      commands::tasks::list_tasks,
+      commands::tasks::toggle_task,
+      commands::templates::list_templates,
+      commands::templates::create_note_from_template,
+      commands::todo::list_todo_items,
+      commands::todo::add_todo_item,
+      commands::todo::complete_todo_item,
+      commands::todo::archive_todo_items,
+      commands::trash::delete_note,
+      commands::trash::list_trash,
+      commands::trash::restore_from_trash,
+      commands::trash::delete_from_trash,
+      commands::trash::empty_trash,
+      commands::trash::get_trash_settings,
+      commands::trash::save_trash_settings
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+// `title_pattern` is a chrono format (`Journal %d-%m-%Y`). The template is the
+// initial body; `{{title}}` and `{{date}}` are replaced when the note is made.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DailyNoteSettings {
+  pub title_pattern: String,
+  pub template: String,
+}
+
+impl Default for DailyNoteSettings {
+  fn default() -> Self {
+    Self {
+      title_pattern: "Journal %d-%m-%Y".to_string(),
+      template: String::new(),
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyNoteDay {
+  pub date: String,
+  pub id: String,
+}
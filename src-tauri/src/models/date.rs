@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+// How `date` and `updated` are written in new and saved notes. Notes in any
+// of these formats are read whatever the setting.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DateFormat {
+  // `17-01-2026` and `17-01-2026 14:32` (local time).
+  #[default]
+  French,
+  // `2026-01-17` and `2026-01-17T14:32:05` (local time).
+  Iso,
+  // `2026-01-17` and `2026-01-17T14:32:05+01:00`.
+  IsoOffset,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DateSettings {
+  pub format: DateFormat,
+}
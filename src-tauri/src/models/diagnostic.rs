@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+  // The note cannot be read as a regular note until this is fixed.
+  Error,
+  // The note works but the value is not in its canonical form.
+  Warning,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DiagnosticFix {
+  pub id: String,
+  pub label: String,
+}
+
+// `line` and `column` are 1-based positions in the note file. `field` is
+// missing for syntax errors that cannot be tied to a frontmatter key.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NoteDiagnostic {
+  pub field: Option<String>,
+  pub line: usize,
+  pub column: usize,
+  pub severity: DiagnosticSeverity,
+  pub message: String,
+  pub fix: Option<DiagnosticFix>,
+}
+
+// `before` is missing when the field was absent.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RepairChange {
+  pub field: String,
+  pub before: Option<String>,
+  pub after: String,
+}
+
+// `rewritten` is set when the file changes, which includes a header that is
+// only reformatted; `unresolved` lists the errors no fix could repair.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteRepair {
+  pub id: String,
+  pub changes: Vec<RepairChange>,
+  pub rewritten: bool,
+  pub unresolved: Vec<NoteDiagnostic>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RepairReport {
+  pub dry_run: bool,
+  pub notes: Vec<NoteRepair>,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteDraft {
+  pub note_id: String,
+  pub content: String,
+  pub stashed_at: String,
+}
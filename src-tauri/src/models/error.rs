@@ -0,0 +1,107 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+// What went wrong, for the UI to react to and localise. `message` is a short
+// English fallback; `details` carries the underlying cause when there is one.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+  // The note, version, template, trash entry... does not exist.
+  NotFound,
+  // A note id, version or entry id that is malformed or outside its folder.
+  InvalidId,
+  // A request the app refuses: missing title, unknown statut, bad setting...
+  InvalidInput,
+  // The file changed since the caller read it, e.g. a task moved lines.
+  StaleRevision,
+  // The note's (or template's) frontmatter has errors.
+  InvalidFrontmatter,
+  // A file the app manages (settings, trash manifest...) does not parse.
+  InvalidData,
+  Io,
+  // A query on the local index failed; rebuilding the index may fix it.
+  IndexCorrupt,
+  Git,
+  Internal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppError {
+  pub code: ErrorCode,
+  pub message: String,
+  pub details: Option<String>,
+}
+
+impl AppError {
+  pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+    Self {
+      code,
+      message: message.into(),
+      details: None,
+    }
+  }
+
+  pub fn with_details(
+    code: ErrorCode,
+    message: impl Into<String>,
+    details: impl fmt::Display,
+  ) -> Self {
+    Self {
+      code,
+      message: message.into(),
+      details: Some(details.to_string()),
+    }
+  }
+
+  pub fn not_found(message: impl Into<String>) -> Self {
+    Self::new(ErrorCode::NotFound, message)
+  }
+
+  pub fn invalid_id(message: impl Into<String>) -> Self {
+    Self::new(ErrorCode::InvalidId, message)
+  }
+
+  pub fn invalid_input(message: impl Into<String>) -> Self {
+    Self::new(ErrorCode::InvalidInput, message)
+  }
+
+  pub fn stale_revision(message: impl Into<String>) -> Self {
+    Self::new(ErrorCode::StaleRevision, message)
+  }
+
+  pub fn io(message: impl Into<String>, error: impl fmt::Display) -> Self {
+    Self::with_details(ErrorCode::Io, message, error)
+  }
+
+  pub fn index(message: impl Into<String>, error: impl fmt::Display) -> Self {
+    Self::with_details(ErrorCode::IndexCorrupt, message, error)
+  }
+
+  pub fn invalid_data(message: impl Into<String>, error: impl fmt::Display) -> Self {
+    Self::with_details(ErrorCode::InvalidData, message, error)
+  }
+
+  pub fn internal(message: impl Into<String>, error: impl fmt::Display) -> Self {
+    Self::with_details(ErrorCode::Internal, message, error)
+  }
+}
+
+// Lets the Git history service use `?` on git2 calls.
+impl From<git2::Error> for AppError {
+  fn from(error: git2::Error) -> Self {
+    AppError::with_details(ErrorCode::Git, "Git error", error.message())
+  }
+}
+
+// Logs and CLI output read like the old string errors: "Read file failed: …".
+impl fmt::Display for AppError {
+  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.details {
+      Some(details) => write!(formatter, "{}: {}", self.message, details),
+      None => write!(formatter, "{}", self.message),
+    }
+  }
+}
+
+impl std::error::Error for AppError {}
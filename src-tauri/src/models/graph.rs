@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphNodeKind {
+  Note,
+  Tag,
+  Unresolved,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphEdgeKind {
+  Link,
+  Tag,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GraphNode {
+  pub id: String,
+  pub kind: GraphNodeKind,
+  pub label: String,
+  pub statut: Option<String>,
+  pub tags: Vec<String>,
+  pub degree: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GraphEdge {
+  pub source: String,
+  pub target: String,
+  pub kind: GraphEdgeKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NoteGraph {
+  pub nodes: Vec<GraphNode>,
+  pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct GraphQuery {
+  pub tag: Option<String>,
+  pub folder: Option<String>,
+  pub around: Option<String>,
+  pub depth: usize,
+  pub include_tags: bool,
+  pub include_unresolved: bool,
+}
+
+impl Default for GraphQuery {
+  fn default() -> Self {
+    Self {
+      tag: None,
+      folder: None,
+      around: None,
+      depth: 1,
+      include_tags: true,
+      include_unresolved: true,
+    }
+  }
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteVersion {
+  pub id: String,
+  pub created: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteCommit {
+  pub id: String,
+  pub summary: String,
+  pub created: String,
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+  Wiki,
+  Markdown,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteLink {
+  pub source_id: String,
+  pub source_title: String,
+  pub kind: LinkKind,
+  pub target: String,
+  pub target_id: Option<String>,
+  pub anchor: String,
+  pub position: usize,
+}
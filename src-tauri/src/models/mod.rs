@@ -1,3 +1,37 @@
+pub mod daily;
+pub mod date;
+pub mod diagnostic;
+pub mod draft;
+pub mod error;
+pub mod graph;
+pub mod history;
+pub mod link;
 pub mod note;
+pub mod query;
+pub mod saved_search;
+pub mod search;
+pub mod section;
+pub mod task;
+pub mod template;
+pub mod todo;
+pub mod trash;
 
-pub use note::{NoteDetail, NoteMetadata};
+pub use daily::{DailyNoteDay, DailyNoteSettings};
+pub use date::{DateFormat, DateSettings};
+pub use diagnostic::{
+  DiagnosticFix, DiagnosticSeverity, NoteDiagnostic, NoteRepair, RepairChange, RepairReport,
+};
+pub use draft::NoteDraft;
+pub use error::{AppError, ErrorCode};
+pub use graph::{GraphEdge, GraphEdgeKind, GraphNode, GraphNodeKind, GraphQuery, NoteGraph};
+pub use history::{NoteCommit, NoteVersion};
+pub use link::{LinkKind, NoteLink};
+pub use note::{NoteDetail, NoteMetadata, NotePage, SavedNote};
+pub use query::{ListQuery, SortDirection, SortKey};
+pub use saved_search::{SavedSearch, SavedSearchCount};
+pub use search::{SearchHit, SearchRanking};
+pub use section::NoteSection;
+pub use task::{NoteTask, TaskQuery};
+pub use template::{NoteTemplate, TemplateNote};
+pub use todo::{TodoItem, TodoQuery};
+pub use trash::{TrashSettings, TrashedNote};
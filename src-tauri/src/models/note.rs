@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::models::NoteDiagnostic;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NoteMetadata {
   pub id: String,
@@ -11,10 +13,30 @@ pub struct NoteMetadata {
   pub tags: Vec<String>,
   pub updated: String,
   pub archived: bool,
+  pub uid: Option<String>,
+  // Set when the frontmatter does not parse or validate; the other fields
+  // then hold whatever could be recovered from it.
+  #[serde(default)]
+  pub invalid: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NoteDetail {
   pub metadata: NoteMetadata,
   pub content: String,
+  #[serde(default)]
+  pub diagnostics: Vec<NoteDiagnostic>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedNote {
+  #[serde(flatten)]
+  pub detail: NoteDetail,
+  pub relinked: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotePage {
+  pub notes: Vec<NoteMetadata>,
+  pub total: usize,
 }
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+  #[default]
+  Created,
+  Updated,
+  Title,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+  Asc,
+  #[default]
+  Desc,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ListQuery {
+  pub sort: SortKey,
+  pub direction: SortDirection,
+  pub statut: Option<String>,
+  pub archived: Option<bool>,
+  pub invalid: Option<bool>,
+  pub tags: Vec<String>,
+  pub offset: usize,
+  pub limit: Option<usize>,
+}
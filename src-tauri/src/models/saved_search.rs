@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedSearch {
+  pub id: String,
+  pub name: String,
+  pub query: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedSearchCount {
+  #[serde(flatten)]
+  pub search: SavedSearch,
+  pub count: usize,
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{NoteMetadata, NoteSection};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SearchRanking {
+  pub title: f64,
+  pub layout: f64,
+  pub date: f64,
+  pub description: f64,
+  pub statut: f64,
+  pub tags: f64,
+  pub updated: f64,
+  pub archived: f64,
+  pub body: f64,
+  pub recency_boost: f64,
+  pub recency_half_life_days: f64,
+}
+
+impl Default for SearchRanking {
+  fn default() -> Self {
+    Self {
+      title: 10.0,
+      layout: 0.01,
+      date: 0.2,
+      description: 3.0,
+      statut: 0.5,
+      tags: 5.0,
+      updated: 0.2,
+      archived: 0.01,
+      body: 1.0,
+      recency_boost: 0.5,
+      recency_half_life_days: 30.0,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchHit {
+  #[serde(flatten)]
+  pub note: NoteMetadata,
+  pub section: Option<NoteSection>,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteSection {
+  pub level: u8,
+  pub heading: String,
+  pub start: usize,
+  pub end: usize,
+  pub line: usize,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteTask {
+  pub note_id: String,
+  pub note_title: String,
+  pub line: usize,
+  pub text: String,
+  pub done: bool,
+  pub due: Option<String>,
+  pub tags: Vec<String>,
+}
+
+// Dates are ISO `YYYY-MM-DD`, like the due dates written in the tasks.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TaskQuery {
+  pub include_done: bool,
+  pub include_archived: bool,
+  pub note_id: Option<String>,
+  pub tag: Option<String>,
+  pub due_before: Option<String>,
+  pub due_after: Option<String>,
+}
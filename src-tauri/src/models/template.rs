@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::NoteDetail;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteTemplate {
+  pub id: String,
+  pub name: String,
+  pub description: String,
+  pub statut: Option<String>,
+  pub tags: Vec<String>,
+  pub prompts: Vec<String>,
+}
+
+// `cursor` is where `{{cursor}}` stood, as a UTF-16 offset into the content
+// so the editor can place the caret directly.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateNote {
+  #[serde(flatten)]
+  pub detail: NoteDetail,
+  pub cursor: Option<usize>,
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TodoItem {
+  pub file: String,
+  pub line: usize,
+  pub raw: String,
+  pub done: bool,
+  pub priority: Option<String>,
+  pub completion_date: Option<String>,
+  pub creation_date: Option<String>,
+  pub text: String,
+  pub projects: Vec<String>,
+  pub contexts: Vec<String>,
+  pub due: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TodoQuery {
+  pub include_done: bool,
+  pub priority: Option<String>,
+  pub project: Option<String>,
+  pub context: Option<String>,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashedNote {
+  pub id: String,
+  pub note_id: String,
+  pub title: String,
+  pub trashed_at: String,
+}
+
+// `purge_after_days` of 0 keeps trashed notes until the trash is emptied.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TrashSettings {
+  pub purge_after_days: u32,
+}
+
+impl Default for TrashSettings {
+  fn default() -> Self {
+    Self {
+      purge_after_days: 30,
+    }
+  }
+}
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, Local, NaiveDate};
+
+use crate::models::{
+  AppError, DailyNoteDay, DailyNoteSettings, ListQuery, NoteDetail, NoteMetadata,
+};
+use crate::services::dates;
+use crate::services::notes_fs::{self, NewNote};
+use crate::services::search;
+
+const DAILY_SETTINGS_FILE: &str = "daily-notes.json";
+
+pub fn get_daily_note_settings() -> Result<DailyNoteSettings, AppError> {
+  let path = settings_path()?;
+  if !path.exists() {
+    return Ok(DailyNoteSettings::default());
+  }
+
+  let raw = fs::read_to_string(&path)
+    .map_err(|error| AppError::io("Read daily note settings failed", error))?;
+  let settings: DailyNoteSettings = serde_json::from_str(&raw)
+    .map_err(|error| AppError::invalid_data("Invalid daily note settings", error))?;
+  validate_title_pattern(&settings.title_pattern)?;
+  Ok(settings)
+}
+
+pub fn save_daily_note_settings(
+  settings: &DailyNoteSettings,
+) -> Result<DailyNoteSettings, AppError> {
+  validate_title_pattern(&settings.title_pattern)?;
+
+  let path = settings_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| AppError::io("Create config dir failed", error))?;
+  }
+
+  let raw = serde_json::to_string_pretty(settings)
+    .map_err(|error| AppError::internal("Serialize daily note settings failed", error))?;
+  fs::write(&path, raw).map_err(|error| AppError::io("Write daily note settings failed", error))?;
+  Ok(settings.clone())
+}
+
+// Returns the daily note for `date` (DD-MM-YYYY or YYYY-MM-DD, today when
+// missing), creating it from the template when the day has no entry yet.
+pub fn open_daily_note(date: Option<&str>) -> Result<NoteDetail, AppError> {
+  let settings = get_daily_note_settings()?;
+  let day = match date {
+    Some(date) => parse_day(date)?,
+    None => Local::now().date_naive(),
+  };
+
+  if let Some((_, note)) = daily_notes(&settings)?
+    .into_iter()
+    .find(|(entry_day, _)| *entry_day == day)
+  {
+    return notes_fs::get_note(&note.id);
+  }
+
+  let title = format_title(day, &settings.title_pattern)?;
+  let body = settings
+    .template
+    .replace("{{title}}", &title)
+    .replace("{{date}}", &dates::format_date(day, dates::vault_format()));
+  // Today's note gets the current time in its file name like any new note;
+  // other days are created at midnight.
+  let now = Local::now().naive_local();
+  let created = if day == now.date() {
+    now
+  } else {
+    day.and_hms_opt(0, 0, 0).unwrap_or(now)
+  };
+
+  let mut note = NewNote::new(&title, created);
+  note.body = body;
+  notes_fs::create_note_with(&note)
+}
+
+// The closest existing daily note strictly before (or after) `date`.
+pub fn adjacent_daily_note(date: &str, forward: bool) -> Result<Option<NoteMetadata>, AppError> {
+  let settings = get_daily_note_settings()?;
+  let day = parse_day(date)?;
+  let notes = daily_notes(&settings)?;
+
+  let adjacent = if forward {
+    notes.into_iter().find(|(entry_day, _)| *entry_day > day)
+  } else {
+    notes
+      .into_iter()
+      .rev()
+      .find(|(entry_day, _)| *entry_day < day)
+  };
+  Ok(adjacent.map(|(_, note)| note))
+}
+
+pub fn daily_note_calendar(year: i32, month: u32) -> Result<Vec<DailyNoteDay>, AppError> {
+  let settings = get_daily_note_settings()?;
+  let format = dates::vault_format();
+  let mut days: Vec<DailyNoteDay> = Vec::new();
+  for (day, note) in daily_notes(&settings)? {
+    if day.year() != year || day.month() != month {
+      continue;
+    }
+    let date = dates::format_date(day, format);
+    if days.last().map(|last| last.date == date).unwrap_or(false) {
+      continue;
+    }
+    days.push(DailyNoteDay { date, id: note.id });
+  }
+
+  Ok(days)
+}
+
+// A note is a daily note when its title parses back with the title pattern.
+// Sorted by day, then by id so duplicates resolve to the oldest file.
+fn daily_notes(settings: &DailyNoteSettings) -> Result<Vec<(NaiveDate, NoteMetadata)>, AppError> {
+  let notes = search::list_notes(&ListQuery::default())?.notes;
+
+  let mut daily: Vec<(NaiveDate, NoteMetadata)> = notes
+    .into_iter()
+    .filter_map(|note| {
+      NaiveDate::parse_from_str(&note.title, &settings.title_pattern)
+        .ok()
+        .map(|day| (day, note))
+    })
+    .collect();
+  daily.sort_by(|left, right| {
+    left
+      .0
+      .cmp(&right.0)
+      .then_with(|| left.1.id.cmp(&right.1.id))
+  });
+  Ok(daily)
+}
+
+// A pattern without the full date could not be parsed back from the title.
+fn validate_title_pattern(pattern: &str) -> Result<(), AppError> {
+  let sample = NaiveDate::from_ymd_opt(2026, 12, 31)
+    .ok_or_else(|| AppError::invalid_input("Invalid sample date"))?;
+  let title = format_title(sample, pattern)?;
+  if NaiveDate::parse_from_str(&title, pattern).ok() != Some(sample) {
+    return Err(AppError::invalid_input(
+      "Daily note title pattern must include the day, month and year",
+    ));
+  }
+
+  Ok(())
+}
+
+// `to_string()` panics when chrono cannot format the pattern, either because
+// it is malformed or because it asks for a time (`%H`) on a plain date.
+fn format_title(day: NaiveDate, pattern: &str) -> Result<String, AppError> {
+  if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+    return Err(AppError::invalid_input("Invalid daily note title pattern"));
+  }
+
+  let mut title = String::new();
+  write!(&mut title, "{}", day.format(pattern))
+    .map_err(|_| AppError::invalid_input("Daily note title pattern cannot format a date"))?;
+  Ok(title)
+}
+
+fn parse_day(date: &str) -> Result<NaiveDate, AppError> {
+  dates::parse_date(date).ok_or_else(|| AppError::invalid_input("Invalid date"))
+}
+
+fn settings_path() -> Result<PathBuf, AppError> {
+  notes_fs::vault_config_dir().map(|dir| dir.join(DAILY_SETTINGS_FILE))
+}
@@ -0,0 +1,163 @@
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::models::{AppError, DateFormat, DateSettings};
+use crate::services::notes_fs;
+
+const DATE_SETTINGS_FILE: &str = "dates.json";
+// Every spelling read as a `date`. Those no setting writes come from other
+// tools: the notes still sort, and repairs rewrite them in the vault format.
+const DATE_FORMATS: [&str; 5] = ["%d-%m-%Y", "%Y-%m-%d", "%d/%m/%Y", "%d.%m.%Y", "%Y/%m/%d"];
+// The same for `updated` values without an offset, which are local time.
+const UPDATED_FORMATS: [&str; 8] = [
+  "%d-%m-%Y %H:%M",
+  "%Y-%m-%dT%H:%M:%S",
+  "%Y-%m-%dT%H:%M",
+  "%Y-%m-%d %H:%M",
+  "%Y-%m-%d %H:%M:%S",
+  "%d-%m-%Y %H:%M:%S",
+  "%d/%m/%Y %H:%M",
+  "%d.%m.%Y %H:%M",
+];
+// Sort keys order as text, so every timestamp is stored in UTC.
+const KEY_DATE_FORMAT: &str = "%Y-%m-%d";
+const KEY_UPDATED_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+// Times the app records itself (versions, drafts, trash). They are stored in
+// local files and read back, so they keep one format whatever the vault's.
+const TIMESTAMP_FORMAT: &str = "%d-%m-%Y %H:%M:%S";
+
+pub fn get_date_settings() -> Result<DateSettings, AppError> {
+  let path = settings_path()?;
+  if !path.exists() {
+    return Ok(DateSettings::default());
+  }
+
+  let raw =
+    fs::read_to_string(&path).map_err(|error| AppError::io("Read date settings failed", error))?;
+  serde_json::from_str(&raw).map_err(|error| AppError::invalid_data("Invalid date settings", error))
+}
+
+pub fn save_date_settings(settings: &DateSettings) -> Result<DateSettings, AppError> {
+  let path = settings_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| AppError::io("Create config dir failed", error))?;
+  }
+
+  let raw = serde_json::to_string_pretty(settings)
+    .map_err(|error| AppError::internal("Serialize date settings failed", error))?;
+  fs::write(&path, raw).map_err(|error| AppError::io("Write date settings failed", error))?;
+  Ok(settings.clone())
+}
+
+// A missing or unreadable settings file must not block saves: it means the
+// default format.
+pub fn vault_format() -> DateFormat {
+  get_date_settings()
+    .map(|settings| settings.format)
+    .unwrap_or_default()
+}
+
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+  let value = value.trim();
+  DATE_FORMATS
+    .iter()
+    .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+// `updated` as a point in time. A bare date counts as midnight.
+pub fn parse_updated(value: &str) -> Option<DateTime<FixedOffset>> {
+  let value = value.trim();
+  if let Ok(updated) = DateTime::parse_from_rfc3339(value) {
+    return Some(updated);
+  }
+
+  UPDATED_FORMATS
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .or_else(|| parse_date(value).and_then(|date| date.and_hms_opt(0, 0, 0)))
+    .and_then(|updated| Local.from_local_datetime(&updated).earliest())
+    .map(|updated| updated.fixed_offset())
+}
+
+pub fn format_date(date: NaiveDate, format: DateFormat) -> String {
+  match format {
+    DateFormat::French => date.format("%d-%m-%Y").to_string(),
+    DateFormat::Iso | DateFormat::IsoOffset => date.format("%Y-%m-%d").to_string(),
+  }
+}
+
+// Formats without an offset write local time; the offset format keeps the
+// value's own offset so a note written in another timezone is left as is.
+pub fn format_updated(updated: DateTime<FixedOffset>, format: DateFormat) -> String {
+  let local = updated.with_timezone(&Local).naive_local();
+  match format {
+    DateFormat::French => local.format("%d-%m-%Y %H:%M").to_string(),
+    DateFormat::Iso => local.format("%Y-%m-%dT%H:%M:%S").to_string(),
+    DateFormat::IsoOffset => updated.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+  }
+}
+
+pub fn today() -> String {
+  format_date(Local::now().date_naive(), vault_format())
+}
+
+pub fn now_updated() -> String {
+  format_updated(Local::now().fixed_offset(), vault_format())
+}
+
+pub fn format_timestamp(time: NaiveDateTime) -> String {
+  time.format(TIMESTAMP_FORMAT).to_string()
+}
+
+pub fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+  NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT).ok()
+}
+
+// The value rewritten in `format`, or None when it is not a date.
+pub fn normalize_date(value: &str, format: DateFormat) -> Option<String> {
+  parse_date(value).map(|date| format_date(date, format))
+}
+
+pub fn normalize_updated(value: &str, format: DateFormat) -> Option<String> {
+  parse_updated(value).map(|updated| format_updated(updated, format))
+}
+
+// How the vault format reads, for messages.
+pub fn date_pattern(format: DateFormat) -> &'static str {
+  match format {
+    DateFormat::French => "DD-MM-YYYY",
+    DateFormat::Iso | DateFormat::IsoOffset => "YYYY-MM-DD",
+  }
+}
+
+pub fn updated_pattern(format: DateFormat) -> &'static str {
+  match format {
+    DateFormat::French => "DD-MM-YYYY HH:MM",
+    DateFormat::Iso => "YYYY-MM-DDTHH:MM:SS",
+    DateFormat::IsoOffset => "YYYY-MM-DDTHH:MM:SS+HH:MM",
+  }
+}
+
+// Index sort keys; empty when the value is not a date, so those sort first.
+pub fn date_key(value: &str) -> String {
+  parse_date(value)
+    .map(|date| date.format(KEY_DATE_FORMAT).to_string())
+    .unwrap_or_default()
+}
+
+pub fn updated_key(value: &str) -> String {
+  parse_updated(value)
+    .map(|updated| {
+      updated
+        .with_timezone(&Utc)
+        .format(KEY_UPDATED_FORMAT)
+        .to_string()
+    })
+    .unwrap_or_default()
+}
+
+fn settings_path() -> Result<PathBuf, AppError> {
+  notes_fs::vault_config_dir().map(|dir| dir.join(DATE_SETTINGS_FILE))
+}
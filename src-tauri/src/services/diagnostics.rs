@@ -1,26 +1,546 @@
-use std::fmt;
+use serde_yaml::{Mapping, Value};
 
-#[derive(Debug)]
-pub struct DiagnosticsError {
-  details: String,
+use crate::models::{
+  AppError, DiagnosticFix, DiagnosticSeverity, ErrorCode, NoteDetail, NoteDiagnostic, NoteRepair,
+  RepairChange, RepairReport,
+};
+use crate::services::{dates, history, notes_fs};
+
+// Offered on notes that are not UTF-8 (see `notes_fs::read_note_file`).
+pub const CONVERT_TO_UTF8_FIX: &str = "convert-utf8";
+
+// A diagnostic with the value its fix writes to the field.
+struct Finding {
+  diagnostic: NoteDiagnostic,
+  repair: Option<Value>,
+}
+
+// Checks the YAML between the `---` lines of note `id`. Every broken field
+// is reported, not only the first one, in file order.
+pub fn check_frontmatter(yaml: &str, id: &str) -> Vec<NoteDiagnostic> {
+  let mut diagnostics: Vec<NoteDiagnostic> = findings(yaml, id)
+    .into_iter()
+    .map(|finding| finding.diagnostic)
+    .collect();
+  diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+  diagnostics
+}
+
+pub fn has_errors(diagnostics: &[NoteDiagnostic]) -> bool {
+  diagnostics
+    .iter()
+    .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+}
+
+// The YAML starts on the line after the opening `---`.
+pub fn yaml_error(error: &serde_yaml::Error) -> NoteDiagnostic {
+  let (line, column) = error
+    .location()
+    .map(|location| (location.line() + 1, location.column()))
+    .unwrap_or((2, 1));
+  let message = error.to_string();
+  let message = message
+    .split(" at line ")
+    .next()
+    .unwrap_or(message.as_str());
+  syntax_error(line, column, format!("Invalid frontmatter: {}", message))
+}
+
+pub fn syntax_error(line: usize, column: usize, message: impl Into<String>) -> NoteDiagnostic {
+  NoteDiagnostic {
+    field: None,
+    line,
+    column,
+    severity: DiagnosticSeverity::Error,
+    message: message.into(),
+    fix: None,
+  }
+}
+
+// The file decodes as `encoding` (e.g. windows-1252) instead of UTF-8.
+pub fn encoding_error(encoding: &str) -> NoteDiagnostic {
+  NoteDiagnostic {
+    fix: Some(DiagnosticFix {
+      id: CONVERT_TO_UTF8_FIX.to_string(),
+      label: "Convert to UTF-8".to_string(),
+    }),
+    ..syntax_error(1, 1, format!("File is encoded as {}, not UTF-8", encoding))
+  }
+}
+
+// Writes the fix's value into the frontmatter and saves the note through
+// the regular save path. Only the fixed field's line changes.
+pub fn apply_fix(id: &str, fix_id: &str) -> Result<NoteDetail, AppError> {
+  let note = notes_fs::get_note(id)?;
+  // The content was decoded on read; writing it back stores it as UTF-8.
+  if fix_id == CONVERT_TO_UTF8_FIX && notes_fs::note_encoding(id)?.is_some() {
+    return notes_fs::replace_note_content(id, &note.content);
+  }
+
+  let (yaml, body) = notes_fs::split_frontmatter(&note.content)
+    .map_err(|diagnostic| AppError::new(ErrorCode::InvalidFrontmatter, diagnostic.message))?;
+
+  let repair = findings(&yaml, id).into_iter().find_map(|finding| {
+    match (finding.diagnostic, finding.repair) {
+      (
+        NoteDiagnostic {
+          field: Some(field),
+          fix: Some(fix),
+          ..
+        },
+        Some(value),
+      ) if fix.id == fix_id => Some((field, value)),
+      _ => None,
+    }
+  });
+  let Some((field, value)) = repair else {
+    return Err(AppError::invalid_input("Fix not available"));
+  };
+
+  let mut mapping = parse_mapping(&yaml).unwrap_or_default();
+  mapping.insert(Value::String(field.clone()), value);
+  let yaml = serde_yaml::to_string(&mapping)
+    .map_err(|error| AppError::internal("Serialize frontmatter failed", error))?;
+  let content = format!("---\n{}---\n{}", yaml, body);
+  let content = notes_fs::repaired_content(id, &note.content, &content, &[field.as_str()]);
+  notes_fs::save_note(id, &content)
+}
+
+// Applies every available fix to every note and converts files in other
+// encodings to UTF-8. Notes with nothing to change are not touched. With
+// `dry_run` nothing is written; the report lists the same changes either
+// way. `updated` is only changed when it is broken.
+pub fn repair_vault(dry_run: bool) -> Result<RepairReport, AppError> {
+  let entries = notes_fs::list_note_entries()?;
+  let mut ids: Vec<String> = entries.into_iter().map(|entry| entry.metadata.id).collect();
+  ids.sort();
+
+  let mut notes = Vec::new();
+  for id in ids {
+    let note = notes_fs::get_note(&id)?;
+    let (mut repair, content) = repair_note(&id, &note.content)?;
+    // The content was decoded on read, so writing it converts it to UTF-8.
+    if let Some(encoding) = notes_fs::note_encoding(&id)? {
+      repair.changes.insert(
+        0,
+        RepairChange {
+          field: "encoding".to_string(),
+          before: Some(encoding.to_string()),
+          after: "UTF-8".to_string(),
+        },
+      );
+      repair.rewritten = true;
+    }
+    if !repair.rewritten && repair.unresolved.is_empty() {
+      continue;
+    }
+
+    // Each rewritten note is snapshotted first, so a bad bulk repair can be
+    // undone from the note history.
+    if repair.rewritten && !dry_run {
+      history::snapshot_note(&id, true)?;
+      notes_fs::replace_note_content(&id, &content)?;
+    }
+    notes.push(repair);
+  }
+
+  Ok(RepairReport { dry_run, notes })
+}
+
+// Maps statut typos and variants ("terminé", "En chantier", "done") to a
+// known statut, defaulting to the one new notes get.
+pub fn closest_statut(value: &str) -> &'static str {
+  let normalized: String = value
+    .trim()
+    .to_lowercase()
+    .chars()
+    .map(|ch| match ch {
+      'é' | 'è' | 'ê' | 'ë' => 'e',
+      'î' | 'ï' => 'i',
+      _ => ch,
+    })
+    .filter(|ch| ch.is_alphanumeric())
+    .collect();
+
+  if let Some(statut) = notes_fs::STATUTS
+    .iter()
+    .find(|statut| normalized == **statut)
+  {
+    return statut;
+  }
+  if normalized.contains("chantier") || normalized.contains("cours") || normalized == "wip" {
+    return "chantier";
+  }
+  if normalized.starts_with("termin") || normalized.starts_with("fini") || normalized == "done" {
+    return "termine";
+  }
+  notes_fs::DEFAULT_STATUT
+}
+
+// The repair of one note and its repaired content. A file without any
+// frontmatter gets a full header built from the defaults.
+fn repair_note(id: &str, raw: &str) -> Result<(NoteRepair, String), AppError> {
+  let text = raw.trim_start_matches('\u{feff}');
+  let (yaml, body) = match notes_fs::split_frontmatter(raw) {
+    Ok(split) => split,
+    // Without its closing line a header cannot be told apart from the body.
+    Err(diagnostic) if text.lines().next().map(str::trim) == Some("---") => {
+      return Ok(unrepairable(id, raw, diagnostic));
+    }
+    Err(_) => (String::new(), text.to_string()),
+  };
+
+  let mut mapping = match parse_mapping(&yaml) {
+    Ok(mapping) => mapping,
+    Err(diagnostic) => return Ok(unrepairable(id, raw, diagnostic)),
+  };
+
+  let mut changes = Vec::new();
+  let mut unresolved = Vec::new();
+  for finding in findings(&yaml, id) {
+    let (Some(field), Some(value)) = (finding.diagnostic.field.clone(), finding.repair) else {
+      if finding.diagnostic.severity == DiagnosticSeverity::Error {
+        unresolved.push(finding.diagnostic);
+      }
+      continue;
+    };
+
+    changes.push(RepairChange {
+      field: field.clone(),
+      before: mapping.get(field.as_str()).map(display_value),
+      after: display_value(&value),
+    });
+    mapping.insert(Value::String(field), value);
+  }
+
+  // Only the changed fields are written, so a header that needs no repair is
+  // left alone, comments and field order included.
+  let content = if changes.is_empty() {
+    raw.to_string()
+  } else {
+    let yaml = serde_yaml::to_string(&mapping)
+      .map_err(|error| AppError::internal("Serialize frontmatter failed", error))?;
+    let repaired = format!("---\n{}---\n{}", yaml, body);
+    let fields: Vec<&str> = changes.iter().map(|change| change.field.as_str()).collect();
+    notes_fs::repaired_content(id, raw, &repaired, &fields)
+  };
+
+  Ok((
+    NoteRepair {
+      id: id.to_string(),
+      rewritten: content != raw,
+      changes,
+      unresolved,
+    },
+    content,
+  ))
+}
+
+fn unrepairable(id: &str, raw: &str, diagnostic: NoteDiagnostic) -> (NoteRepair, String) {
+  (
+    NoteRepair {
+      id: id.to_string(),
+      changes: Vec::new(),
+      rewritten: false,
+      unresolved: vec![diagnostic],
+    },
+    raw.to_string(),
+  )
+}
+
+fn findings(yaml: &str, id: &str) -> Vec<Finding> {
+  let mapping = match parse_mapping(yaml) {
+    Ok(mapping) => mapping,
+    Err(diagnostic) => {
+      return vec![Finding {
+        diagnostic,
+        repair: None,
+      }]
+    }
+  };
+
+  let mut found = Vec::new();
+  let mut report = |field: &str, severity, message: String, repair: Option<Value>| {
+    found.push(finding(yaml, field, severity, message, repair));
+  };
+  let error = DiagnosticSeverity::Error;
+  let warning = DiagnosticSeverity::Warning;
+  // Dates in another accepted format are read fine, but flagged so repairs
+  // bring them to the vault's format.
+  let format = dates::vault_format();
+
+  match mapping.get("title") {
+    Some(Value::String(title)) if !title.trim().is_empty() => {}
+    None | Some(Value::Null) | Some(Value::String(_)) => report(
+      "title",
+      error,
+      "Missing title".to_string(),
+      Some(Value::String(notes_fs::title_from_id(id))),
+    ),
+    Some(other) => report(
+      "title",
+      error,
+      "Title must be text".to_string(),
+      scalar_text(other).map(Value::String),
+    ),
+  }
+
+  match mapping.get("layout") {
+    Some(Value::String(layout)) if layout == notes_fs::DEFAULT_LAYOUT => {}
+    missing => report(
+      "layout",
+      error,
+      if matches!(missing, None | Some(Value::Null)) {
+        "Missing layout".to_string()
+      } else {
+        "Invalid layout".to_string()
+      },
+      Some(Value::String(notes_fs::DEFAULT_LAYOUT.to_string())),
+    ),
+  }
+
+  match mapping.get("date") {
+    Some(Value::String(date)) if !date.trim().is_empty() => {
+      match dates::normalize_date(date, format) {
+        Some(normalized) if normalized == *date => {}
+        Some(normalized) => report(
+          "date",
+          warning,
+          format!("Date should be {}", dates::date_pattern(format)),
+          Some(Value::String(normalized)),
+        ),
+        None => report(
+          "date",
+          warning,
+          "Date is not a valid date".to_string(),
+          None,
+        ),
+      }
+    }
+    _ => {
+      let date = id
+        .get(..10)
+        .and_then(|date| dates::normalize_date(date, format))
+        .unwrap_or_else(dates::today);
+      report(
+        "date",
+        error,
+        "Missing date".to_string(),
+        Some(Value::String(date)),
+      )
+    }
+  }
+
+  match mapping.get("description") {
+    None | Some(Value::String(_)) => {}
+    Some(Value::Null) => report(
+      "description",
+      error,
+      "Description must be text".to_string(),
+      Some(Value::String(String::new())),
+    ),
+    Some(other) => report(
+      "description",
+      error,
+      "Description must be text".to_string(),
+      scalar_text(other).map(Value::String),
+    ),
+  }
+
+  match mapping.get("statut") {
+    Some(Value::String(statut)) if notes_fs::STATUTS.contains(&statut.as_str()) => {}
+    Some(Value::String(statut)) => report(
+      "statut",
+      error,
+      format!("Invalid statut \"{}\"", statut),
+      Some(Value::String(closest_statut(statut).to_string())),
+    ),
+    _ => report(
+      "statut",
+      error,
+      "Missing statut".to_string(),
+      Some(Value::String(notes_fs::DEFAULT_STATUT.to_string())),
+    ),
+  }
+
+  match mapping.get("tags") {
+    None | Some(Value::Null) => {}
+    Some(Value::Sequence(tags)) if tags.iter().all(Value::is_string) => {}
+    Some(Value::Sequence(tags)) => report(
+      "tags",
+      error,
+      "Tags must be text".to_string(),
+      Some(Value::Sequence(
+        tags
+          .iter()
+          .filter_map(scalar_text)
+          .map(Value::String)
+          .collect(),
+      )),
+    ),
+    Some(Value::String(tags)) => report(
+      "tags",
+      error,
+      "Tags must be a list".to_string(),
+      Some(Value::Sequence(
+        tags
+          .split(',')
+          .map(str::trim)
+          .filter(|tag| !tag.is_empty())
+          .map(|tag| Value::String(tag.to_string()))
+          .collect(),
+      )),
+    ),
+    Some(other) => report(
+      "tags",
+      error,
+      "Tags must be a list".to_string(),
+      scalar_text(other).map(|tag| Value::Sequence(vec![Value::String(tag)])),
+    ),
+  }
+
+  match mapping.get("updated") {
+    Some(Value::String(updated)) if !updated.trim().is_empty() => {
+      match dates::normalize_updated(updated, format) {
+        Some(normalized) if normalized == *updated => {}
+        Some(normalized) => report(
+          "updated",
+          warning,
+          format!("Updated should be {}", dates::updated_pattern(format)),
+          Some(Value::String(normalized)),
+        ),
+        None => report(
+          "updated",
+          warning,
+          "Updated is not a valid date and time".to_string(),
+          None,
+        ),
+      }
+    }
+    _ => report(
+      "updated",
+      error,
+      "Missing updated".to_string(),
+      Some(Value::String(dates::now_updated())),
+    ),
+  }
+
+  match mapping.get("archived") {
+    Some(Value::Bool(_)) => {}
+    None | Some(Value::Null) => report(
+      "archived",
+      error,
+      "Missing archived".to_string(),
+      Some(Value::Bool(false)),
+    ),
+    Some(other) => {
+      let archived = scalar_text(other)
+        .map(|text| {
+          matches!(
+            text.trim().to_lowercase().as_str(),
+            "true" | "yes" | "oui" | "1"
+          )
+        })
+        .unwrap_or(false);
+      report(
+        "archived",
+        error,
+        "Archived must be true or false".to_string(),
+        Some(Value::Bool(archived)),
+      )
+    }
+  }
+
+  match mapping.get("uid") {
+    None | Some(Value::Null) | Some(Value::String(_)) => {}
+    Some(other) => report(
+      "uid",
+      error,
+      "Uid must be text".to_string(),
+      scalar_text(other).map(Value::String),
+    ),
+  }
+
+  found
 }
 
-impl DiagnosticsError {
-  pub fn new(details: impl Into<String>) -> Self {
-    Self {
-      details: details.into(),
+fn finding(
+  yaml: &str,
+  field: &str,
+  severity: DiagnosticSeverity,
+  message: String,
+  repair: Option<Value>,
+) -> Finding {
+  let (line, column) = field_location(yaml, field);
+  let fix = repair.as_ref().map(|value| DiagnosticFix {
+    id: format!("set-{}", field),
+    label: format!("Set {} to {}", field, display_value(value)),
+  });
+
+  Finding {
+    diagnostic: NoteDiagnostic {
+      field: Some(field.to_string()),
+      line,
+      column,
+      severity,
+      message,
+      fix,
+    },
+    repair,
+  }
+}
+
+// Position of the field's value in the file, or the closing `---` when the
+// field is missing.
+fn field_location(yaml: &str, field: &str) -> (usize, usize) {
+  let key = format!("{}:", field);
+  for (index, line) in yaml.lines().enumerate() {
+    if let Some(rest) = line.strip_prefix(&key) {
+      let value_start = key.len() + rest.len() - rest.trim_start().len();
+      return (index + 2, line[..value_start].chars().count() + 1);
     }
   }
+
+  (yaml.lines().count() + 2, 1)
 }
 
-impl fmt::Display for DiagnosticsError {
-  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(formatter, "{}", self.details)
+// An empty frontmatter is an empty mapping, so every required field is
+// reported as missing.
+fn parse_mapping(yaml: &str) -> Result<Mapping, NoteDiagnostic> {
+  if yaml.trim().is_empty() {
+    return Ok(Mapping::new());
+  }
+
+  match serde_yaml::from_str::<Value>(yaml).map_err(|error| yaml_error(&error))? {
+    Value::Mapping(mapping) => Ok(mapping),
+    Value::Null => Ok(Mapping::new()),
+    _ => Err(syntax_error(
+      2,
+      1,
+      "Frontmatter must be a list of `key: value` fields",
+    )),
   }
 }
 
-impl std::error::Error for DiagnosticsError {}
+fn scalar_text(value: &Value) -> Option<String> {
+  match value {
+    Value::String(text) => Some(text.clone()),
+    Value::Bool(flag) => Some(flag.to_string()),
+    Value::Number(number) => Some(number.to_string()),
+    _ => None,
+  }
+}
 
-pub fn collect_diagnostics() -> Result<(), DiagnosticsError> {
-  Ok(())
+fn display_value(value: &Value) -> String {
+  match value {
+    Value::Sequence(items) => format!(
+      "[{}]",
+      items
+        .iter()
+        .filter_map(scalar_text)
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+    other => scalar_text(other).unwrap_or_default(),
+  }
 }
@@ -0,0 +1,103 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::models::{AppError, NoteDraft};
+use crate::services::{dates, indexer, notes_fs};
+
+// The editor stashes its buffer here between autosaves, and keeps it when a
+// save is rejected, so a crash or a failed save never loses text. Drafts stay
+// on this device: the data dir is outside the synced folder.
+const DRAFTS_DIR: &str = "drafts";
+const DRAFT_EXTENSION: &str = ".json";
+
+// One draft per note, replaced on every stash. The file is written next to
+// its final name and renamed over it so a crash mid-write keeps the previous
+// draft intact.
+pub fn stash_draft(id: &str, content: &str) -> Result<NoteDraft, AppError> {
+  let path = draft_path(id)?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| AppError::io("Create drafts dir failed", error))?;
+  }
+
+  let draft = NoteDraft {
+    note_id: id.to_string(),
+    content: content.to_string(),
+    stashed_at: dates::format_timestamp(Local::now().naive_local()),
+  };
+  let raw = serde_json::to_string(&draft)
+    .map_err(|error| AppError::internal("Serialize draft failed", error))?;
+  let temp_path = path.with_extension("json.tmp");
+  fs::write(&temp_path, raw).map_err(|error| AppError::io("Write draft failed", error))?;
+  fs::rename(&temp_path, &path).map_err(|error| AppError::io("Write draft failed", error))?;
+  Ok(draft)
+}
+
+// Drafts that differ from the note on disk, newest first. Drafts matching
+// the file were saved after all and are dropped.
+pub fn list_recoverable_drafts() -> Result<Vec<NoteDraft>, AppError> {
+  let dir = drafts_dir()?;
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let entries = fs::read_dir(&dir).map_err(|error| AppError::io("Read drafts failed", error))?;
+  let mut drafts = Vec::new();
+  for entry in entries {
+    let entry = entry.map_err(|error| AppError::io("Read entry failed", error))?;
+    let path = entry.path();
+    if !path.to_string_lossy().ends_with(DRAFT_EXTENSION) {
+      continue;
+    }
+
+    let Some(draft) = read_draft(&path) else {
+      continue;
+    };
+    let saved = notes_fs::get_note(&draft.note_id)
+      .map(|note| note.content == draft.content)
+      .unwrap_or(false);
+    if saved {
+      remove_file(&path)?;
+      continue;
+    }
+    drafts.push(draft);
+  }
+
+  drafts.sort_by_key(|draft| Reverse(dates::parse_timestamp(&draft.stashed_at)));
+  Ok(drafts)
+}
+
+pub fn get_draft(id: &str) -> Result<NoteDraft, AppError> {
+  read_draft(&draft_path(id)?).ok_or_else(|| AppError::not_found("Draft not found"))
+}
+
+pub fn discard_draft(id: &str) -> Result<(), AppError> {
+  remove_file(&draft_path(id)?)
+}
+
+fn read_draft(path: &Path) -> Option<NoteDraft> {
+  let raw = fs::read_to_string(path).ok()?;
+  serde_json::from_str(&raw).ok()
+}
+
+fn remove_file(path: &Path) -> Result<(), AppError> {
+  if !path.exists() {
+    return Ok(());
+  }
+
+  fs::remove_file(path).map_err(|error| AppError::io("Delete draft failed", error))
+}
+
+fn draft_path(id: &str) -> Result<PathBuf, AppError> {
+  if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+    return Err(AppError::invalid_id("Invalid note ID"));
+  }
+
+  Ok(drafts_dir()?.join(format!("{}{}", id, DRAFT_EXTENSION)))
+}
+
+fn drafts_dir() -> Result<PathBuf, AppError> {
+  indexer::data_dir().map(|dir| dir.join(DRAFTS_DIR))
+}
@@ -0,0 +1,238 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone};
+use git2::{Commit, IndexAddOption, Oid, Repository, Signature, Sort};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AppError, NoteCommit, NoteDetail};
+use crate::services::{dates, history, indexer, notes_fs};
+
+const SETTINGS_FILE: &str = "git-history.json";
+const REPO_DIR: &str = "git";
+// Syncthing's entries never belong in the repository, nor does a `.git`
+// left in the notes folder by an older version.
+const EXCLUDES: &str = ".stversions/\n.stfolder/\n.stignore\n.git\n";
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+struct GitHistorySettings {
+  enabled: bool,
+}
+
+pub fn is_enabled() -> Result<bool, AppError> {
+  let path = settings_path()?;
+  if !path.exists() {
+    return Ok(false);
+  }
+
+  let raw =
+    fs::read_to_string(&path).map_err(|error| AppError::io("Read git settings failed", error))?;
+  let settings: GitHistorySettings = serde_json::from_str(&raw)
+    .map_err(|error| AppError::invalid_data("Invalid git settings", error))?;
+  Ok(settings.enabled)
+}
+
+// Enabling creates the repository (if there is none yet) and records the
+// current state as the first commit. Disabling only stops new commits; the
+// repository is left alone.
+//
+// Each device keeps its own repository, and its own enable flag, in the
+// local data folder, with the notes folder as the working tree. A `.git`
+// inside the synced folder would be written by every device at once.
+pub fn set_enabled(enabled: bool) -> Result<bool, AppError> {
+  let path = settings_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| AppError::io("Create data dir failed", error))?;
+  }
+  let raw = serde_json::to_string_pretty(&GitHistorySettings { enabled })
+    .map_err(|error| AppError::internal("Serialize git settings failed", error))?;
+  fs::write(&path, raw).map_err(|error| AppError::io("Write git settings failed", error))?;
+
+  if enabled {
+    let repo_dir = repo_dir()?;
+    if Repository::open(&repo_dir).is_err() {
+      Repository::init_bare(&repo_dir)?;
+    }
+    write_excludes(&open_repo()?)?;
+    commit_all()?;
+  }
+
+  Ok(enabled)
+}
+
+// Commits every change in the notes folder; called once per watcher batch.
+// Returns the new commit id, or None when disabled or nothing changed.
+pub fn commit_all() -> Result<Option<String>, AppError> {
+  if !is_enabled()? {
+    return Ok(None);
+  }
+
+  let repo = open_repo()?;
+  let mut index = repo.index()?;
+  index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+  index.update_all(["*"], None)?;
+  index.write()?;
+  let tree = repo.find_tree(index.write_tree()?)?;
+
+  let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+  let parent_tree = parent.as_ref().map(|commit| commit.tree()).transpose()?;
+  if parent_tree.as_ref().map(|parent| parent.id()) == Some(tree.id()) {
+    return Ok(None);
+  }
+
+  let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+  let changed: Vec<String> = diff
+    .deltas()
+    .filter_map(|delta| {
+      delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|path| path.to_string_lossy().to_string())
+    })
+    .collect();
+  let message = match changed.as_slice() {
+    [single] => format!("Update {}", single),
+    files => format!("Update {} files", files.len()),
+  };
+
+  let signature = repo
+    .signature()
+    .or_else(|_| Signature::now("Velocitext", "velocitext@localhost"))?;
+  let parents: Vec<&Commit<'_>> = parent.iter().collect();
+  let oid = repo.commit(
+    Some("HEAD"),
+    &signature,
+    &signature,
+    &message,
+    &tree,
+    &parents,
+  )?;
+  Ok(Some(oid.to_string()))
+}
+
+// Commits in which the note's file changed, newest first.
+pub fn note_log(id: &str) -> Result<Vec<NoteCommit>, AppError> {
+  let path = note_path(id)?;
+  let repo = open_repo()?;
+  if repo.head().is_err() {
+    return Ok(Vec::new());
+  }
+
+  let mut walk = repo.revwalk()?;
+  walk.push_head()?;
+  walk.set_sorting(Sort::TIME)?;
+
+  let mut commits = Vec::new();
+  for oid in walk {
+    let commit = repo.find_commit(oid?)?;
+    let current = blob_id(&commit, &path);
+    let previous = match commit.parent(0) {
+      Ok(parent) => blob_id(&parent, &path),
+      Err(_) => None,
+    };
+    if current == previous {
+      continue;
+    }
+
+    let created = Local
+      .timestamp_opt(commit.time().seconds(), 0)
+      .single()
+      .map(|time| dates::format_timestamp(time.naive_local()))
+      .unwrap_or_default();
+    commits.push(NoteCommit {
+      id: commit.id().to_string(),
+      summary: commit.summary().unwrap_or_default().to_string(),
+      created,
+    });
+  }
+
+  Ok(commits)
+}
+
+pub fn get_note_at(id: &str, commit: &str) -> Result<String, AppError> {
+  let path = note_path(id)?;
+  let repo = open_repo()?;
+  let commit = find_commit(&repo, commit)?;
+  let entry = commit
+    .tree()?
+    .get_path(&path)
+    .map_err(|_| AppError::not_found("Note not found in that commit"))?;
+  let blob = entry.to_object(&repo)?.peel_to_blob()?;
+  Ok(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
+// Diff between two commits, or from a commit to the current file when `to`
+// is missing.
+pub fn diff_note_commits(id: &str, from: &str, to: Option<&str>) -> Result<String, AppError> {
+  let old = get_note_at(id, from)?;
+  let (new, new_label) = match to {
+    Some(to) => (get_note_at(id, to)?, to.to_string()),
+    None => (notes_fs::get_note(id)?.content, "current".to_string()),
+  };
+  Ok(history::unified_diff(&old, &new, from, &new_label))
+}
+
+pub fn restore_note_commit(id: &str, commit: &str) -> Result<NoteDetail, AppError> {
+  let content = get_note_at(id, commit)?;
+  history::snapshot_note(id, true)?;
+  notes_fs::save_note(id, &content)
+}
+
+fn blob_id(commit: &Commit<'_>, path: &Path) -> Option<Oid> {
+  commit
+    .tree()
+    .ok()
+    .and_then(|tree| tree.get_path(path).ok())
+    .map(|entry| entry.id())
+}
+
+fn find_commit<'repo>(repo: &'repo Repository, commit: &str) -> Result<Commit<'repo>, AppError> {
+  let oid = Oid::from_str(commit).map_err(|_| AppError::invalid_input("Invalid commit id"))?;
+  repo
+    .find_commit(oid)
+    .map_err(|_| AppError::not_found("Commit not found"))
+}
+
+// The repository is created bare so nothing is written to the notes folder;
+// the working tree is attached on every open instead.
+fn open_repo() -> Result<Repository, AppError> {
+  let repo = Repository::open(repo_dir()?)
+    .map_err(|_| AppError::invalid_input("Git history is not enabled"))?;
+  repo.set_workdir(&notes_dir()?, false)?;
+  Ok(repo)
+}
+
+fn write_excludes(repo: &Repository) -> Result<(), AppError> {
+  let info = repo.path().join("info");
+  fs::create_dir_all(&info).map_err(|error| AppError::io("Create git info dir failed", error))?;
+  let exclude = info.join("exclude");
+  let existing = fs::read_to_string(&exclude).unwrap_or_default();
+  if existing.contains(EXCLUDES) {
+    return Ok(());
+  }
+
+  fs::write(&exclude, format!("{}{}", existing, EXCLUDES))
+    .map_err(|error| AppError::io("Write git excludes failed", error))
+}
+
+fn note_path(id: &str) -> Result<PathBuf, AppError> {
+  if id.contains(['/', '\\']) || id.contains("..") || notes_fs::is_ignored_path(Path::new(id)) {
+    return Err(AppError::invalid_id("Invalid note ID"));
+  }
+
+  Ok(PathBuf::from(id))
+}
+
+fn notes_dir() -> Result<PathBuf, AppError> {
+  notes_fs::notes_dir()
+}
+
+fn repo_dir() -> Result<PathBuf, AppError> {
+  indexer::data_dir().map(|dir| dir.join(REPO_DIR))
+}
+
+fn settings_path() -> Result<PathBuf, AppError> {
+  indexer::data_dir().map(|dir| dir.join(SETTINGS_FILE))
+}
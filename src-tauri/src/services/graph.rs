@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::models::{
+  AppError, ErrorCode, GraphEdge, GraphEdgeKind, GraphNode, GraphNodeKind, GraphQuery, ListQuery,
+  NoteGraph, NoteLink, NoteMetadata,
+};
+use crate::services::{indexer, links, search};
+
+pub fn note_graph(query: &GraphQuery) -> Result<NoteGraph, AppError> {
+  check_folder(query.folder.as_deref())?;
+  let notes = search::list_notes(&ListQuery::default())?.notes;
+  let all_links = links::list_all_links()?;
+
+  let mut kept: BTreeMap<String, NoteMetadata> = notes
+    .into_iter()
+    .filter(|note| matches_tag(note, query.tag.as_deref()))
+    .map(|note| (note.id.clone(), note))
+    .collect();
+
+  if let Some(around) = &query.around {
+    let root = indexer::resolve_note_id(around)?;
+    let reachable = neighbourhood(&root, query.depth, &kept, &all_links);
+    kept.retain(|id, _| reachable.contains(id));
+  }
+
+  let mut nodes: BTreeMap<String, GraphNode> = BTreeMap::new();
+  let mut edges = Vec::new();
+
+  for note in kept.values() {
+    nodes.insert(
+      note.id.clone(),
+      GraphNode {
+        id: note.id.clone(),
+        kind: GraphNodeKind::Note,
+        label: note.title.clone(),
+        statut: Some(note.statut.clone()),
+        tags: note.tags.clone(),
+        degree: 0,
+      },
+    );
+  }
+
+  let mut seen_links = HashSet::new();
+  for link in &all_links {
+    if !kept.contains_key(&link.source_id) {
+      continue;
+    }
+
+    let target = match &link.target_id {
+      Some(target_id) if kept.contains_key(target_id) => target_id.clone(),
+      Some(_) => continue,
+      None if query.include_unresolved => {
+        let id = format!("unresolved:{}", link.target);
+        nodes.entry(id.clone()).or_insert_with(|| GraphNode {
+          id: id.clone(),
+          kind: GraphNodeKind::Unresolved,
+          label: link.target.clone(),
+          statut: None,
+          tags: Vec::new(),
+          degree: 0,
+        });
+        id
+      }
+      None => continue,
+    };
+
+    if seen_links.insert((link.source_id.clone(), target.clone())) {
+      edges.push(GraphEdge {
+        source: link.source_id.clone(),
+        target,
+        kind: GraphEdgeKind::Link,
+      });
+    }
+  }
+
+  if query.include_tags {
+    for note in kept.values() {
+      for tag in &note.tags {
+        let id = format!("tag:{}", tag.to_lowercase());
+        nodes.entry(id.clone()).or_insert_with(|| GraphNode {
+          id: id.clone(),
+          kind: GraphNodeKind::Tag,
+          label: tag.clone(),
+          statut: None,
+          tags: Vec::new(),
+          degree: 0,
+        });
+        edges.push(GraphEdge {
+          source: note.id.clone(),
+          target: id,
+          kind: GraphEdgeKind::Tag,
+        });
+      }
+    }
+  }
+
+  for edge in &edges {
+    if let Some(node) = nodes.get_mut(&edge.source) {
+      node.degree += 1;
+    }
+    if let Some(node) = nodes.get_mut(&edge.target) {
+      node.degree += 1;
+    }
+  }
+
+  Ok(NoteGraph {
+    nodes: nodes.into_values().collect(),
+    edges,
+  })
+}
+
+pub fn to_json(graph: &NoteGraph) -> Result<String, AppError> {
+  serde_json::to_string_pretty(graph)
+    .map_err(|error| AppError::internal("Serialize graph failed", error))
+}
+
+pub fn to_dot(graph: &NoteGraph) -> String {
+  let mut dot = String::from("digraph velocitext {\n  node [fontname=\"sans-serif\"];\n");
+
+  for node in &graph.nodes {
+    let attributes = match node.kind {
+      GraphNodeKind::Note => format!(
+        "label=\"{}\", shape=box, statut=\"{}\", tags=\"{}\", degree={}",
+        escape_dot(&node.label),
+        escape_dot(node.statut.as_deref().unwrap_or_default()),
+        escape_dot(&node.tags.join(",")),
+        node.degree
+      ),
+      GraphNodeKind::Tag => format!("label=\"#{}\", shape=ellipse", escape_dot(&node.label)),
+      GraphNodeKind::Unresolved => format!(
+        "label=\"{}\", shape=box, style=dashed",
+        escape_dot(&node.label)
+      ),
+    };
+    dot.push_str(&format!(
+      "  \"{}\" [{}];\n",
+      escape_dot(&node.id),
+      attributes
+    ));
+  }
+
+  for edge in &graph.edges {
+    let style = match edge.kind {
+      GraphEdgeKind::Link => "",
+      GraphEdgeKind::Tag => " [style=dotted, arrowhead=none]",
+    };
+    dot.push_str(&format!(
+      "  \"{}\" -> \"{}\"{};\n",
+      escape_dot(&edge.source),
+      escape_dot(&edge.target),
+      style
+    ));
+  }
+
+  dot.push_str("}\n");
+  dot
+}
+
+pub fn export(query: &GraphQuery, format: &str) -> Result<String, AppError> {
+  let graph = note_graph(query)?;
+  match format {
+    "json" => to_json(&graph),
+    "dot" => Ok(to_dot(&graph)),
+    other => Err(AppError::io("Unknown graph format", other)),
+  }
+}
+
+// Links are followed in both directions: a note's neighbourhood includes the
+// notes it points to and the notes pointing at it.
+fn neighbourhood(
+  root: &str,
+  depth: usize,
+  notes: &BTreeMap<String, NoteMetadata>,
+  all_links: &[NoteLink],
+) -> HashSet<String> {
+  let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+  for link in all_links {
+    if let Some(target) = link.target_id.as_deref() {
+      adjacency
+        .entry(link.source_id.as_str())
+        .or_default()
+        .push(target);
+      adjacency
+        .entry(target)
+        .or_default()
+        .push(link.source_id.as_str());
+    }
+  }
+
+  let mut reachable = HashSet::new();
+  if !notes.contains_key(root) {
+    return reachable;
+  }
+
+  let mut queue = VecDeque::from([(root.to_string(), 0)]);
+  reachable.insert(root.to_string());
+  while let Some((id, distance)) = queue.pop_front() {
+    if distance >= depth {
+      continue;
+    }
+    for next in adjacency.get(id.as_str()).into_iter().flatten() {
+      if notes.contains_key(*next) && reachable.insert(next.to_string()) {
+        queue.push_back((next.to_string(), distance + 1));
+      }
+    }
+  }
+
+  reachable
+}
+
+fn matches_tag(note: &NoteMetadata, tag: Option<&str>) -> bool {
+  match tag {
+    Some(tag) => note
+      .tags
+      .iter()
+      .any(|candidate| candidate.to_lowercase() == tag.to_lowercase()),
+    None => true,
+  }
+}
+
+// Notes all sit at the root of the notes folder, so the root ("" or "/") is
+// the only folder there is; it selects every note. Any other folder is refused
+// rather than silently exporting an empty graph.
+fn check_folder(folder: Option<&str>) -> Result<(), AppError> {
+  match folder {
+    Some(folder) if !folder.trim_matches('/').is_empty() => Err(AppError::with_details(
+      ErrorCode::InvalidInput,
+      "Notes are not in subfolders, only the root folder can be selected",
+      folder,
+    )),
+    _ => Ok(()),
+  }
+}
+
+fn escape_dot(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\n")
+}
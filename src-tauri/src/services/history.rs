@@ -0,0 +1,182 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local, NaiveDateTime};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use similar::TextDiff;
+
+use crate::models::{AppError, NoteDetail, NoteVersion};
+use crate::services::{dates, indexer, notes_fs};
+
+const HISTORY_DIR: &str = "history";
+const SNAPSHOT_EXTENSION: &str = ".md.gz";
+const VERSION_FORMAT: &str = "%Y%m%d-%H%M%S";
+// Autosave writes every few seconds: a new snapshot is only taken when the
+// newest one is older than this, so a burst of edits keeps the version from
+// before the burst.
+const SNAPSHOT_INTERVAL_MINUTES: i64 = 5;
+// Retention: everything from the last day, then one version per hour for a
+// week, then one per day for three months.
+const KEEP_ALL_HOURS: i64 = 24;
+const HOURLY_DAYS: i64 = 7;
+const DAILY_DAYS: i64 = 90;
+
+// Stores the note's current file as a snapshot before it is overwritten.
+// `force` skips the interval check, e.g. before a restore.
+pub fn snapshot_note(id: &str, force: bool) -> Result<(), AppError> {
+  let note = notes_fs::get_note(id)?;
+  let dir = history_dir(&note)?;
+  let now = Local::now().naive_local();
+
+  if let Some(newest) = read_versions(&dir)?.first() {
+    if !force && now - newest.0 < Duration::minutes(SNAPSHOT_INTERVAL_MINUTES) {
+      return Ok(());
+    }
+    if read_snapshot(&dir, &newest.1)? == note.content {
+      return Ok(());
+    }
+  }
+
+  fs::create_dir_all(&dir).map_err(|error| AppError::io("Create history dir failed", error))?;
+  let version = now.format(VERSION_FORMAT).to_string();
+  let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+  encoder
+    .write_all(note.content.as_bytes())
+    .map_err(|error| AppError::io("Compress snapshot failed", error))?;
+  let compressed = encoder
+    .finish()
+    .map_err(|error| AppError::io("Compress snapshot failed", error))?;
+  fs::write(
+    dir.join(format!("{}{}", version, SNAPSHOT_EXTENSION)),
+    compressed,
+  )
+  .map_err(|error| AppError::io("Write snapshot failed", error))?;
+
+  prune(&dir, now)
+}
+
+pub fn list_versions(id: &str) -> Result<Vec<NoteVersion>, AppError> {
+  let note = notes_fs::get_note(id)?;
+  let dir = history_dir(&note)?;
+  Ok(
+    read_versions(&dir)?
+      .into_iter()
+      .map(|(created, version)| NoteVersion {
+        id: version,
+        created: dates::format_timestamp(created),
+      })
+      .collect(),
+  )
+}
+
+pub fn get_version(id: &str, version: &str) -> Result<String, AppError> {
+  let note = notes_fs::get_note(id)?;
+  read_snapshot(&history_dir(&note)?, version)
+}
+
+// Unified diff from the stored version to the current text.
+pub fn diff_version(id: &str, version: &str) -> Result<String, AppError> {
+  let note = notes_fs::get_note(id)?;
+  let old = read_snapshot(&history_dir(&note)?, version)?;
+  Ok(unified_diff(&old, &note.content, version, "current"))
+}
+
+// The current text is snapshotted first so a restore can itself be undone.
+// The version goes through the regular save path and may rename the note.
+pub fn restore_version(id: &str, version: &str) -> Result<NoteDetail, AppError> {
+  let content = get_version(id, version)?;
+  snapshot_note(id, true)?;
+  notes_fs::save_note(id, &content)
+}
+
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+  TextDiff::from_lines(old, new)
+    .unified_diff()
+    .context_radius(3)
+    .header(old_label, new_label)
+    .to_string()
+}
+
+// History follows the note's uid so it survives renames; notes created
+// before uids existed fall back to their file name.
+fn history_dir(note: &NoteDetail) -> Result<PathBuf, AppError> {
+  let key = note
+    .metadata
+    .uid
+    .clone()
+    .unwrap_or_else(|| note.metadata.id.clone());
+  if key.contains(['/', '\\']) || key.contains("..") {
+    return Err(AppError::invalid_input("Invalid history key"));
+  }
+
+  indexer::data_dir().map(|dir| dir.join(HISTORY_DIR).join(key))
+}
+
+// Snapshots sorted newest first.
+fn read_versions(dir: &Path) -> Result<Vec<(NaiveDateTime, String)>, AppError> {
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let entries = fs::read_dir(dir).map_err(|error| AppError::io("Read history failed", error))?;
+  let mut versions = Vec::new();
+  for entry in entries {
+    let entry = entry.map_err(|error| AppError::io("Read entry failed", error))?;
+    let name = entry.file_name().to_string_lossy().to_string();
+    let Some(version) = name.strip_suffix(SNAPSHOT_EXTENSION) else {
+      continue;
+    };
+    if let Ok(created) = NaiveDateTime::parse_from_str(version, VERSION_FORMAT) {
+      versions.push((created, version.to_string()));
+    }
+  }
+
+  versions.sort_by_key(|version| Reverse(version.0));
+  Ok(versions)
+}
+
+fn read_snapshot(dir: &Path, version: &str) -> Result<String, AppError> {
+  if NaiveDateTime::parse_from_str(version, VERSION_FORMAT).is_err() {
+    return Err(AppError::invalid_id("Invalid version"));
+  }
+
+  let path = dir.join(format!("{}{}", version, SNAPSHOT_EXTENSION));
+  let compressed = fs::read(&path).map_err(|_| AppError::not_found("Version not found"))?;
+  let mut content = String::new();
+  GzDecoder::new(compressed.as_slice())
+    .read_to_string(&mut content)
+    .map_err(|error| AppError::io("Read snapshot failed", error))?;
+  Ok(content)
+}
+
+// Keeps the newest snapshot of each retention bucket and deletes the rest.
+fn prune(dir: &Path, now: NaiveDateTime) -> Result<(), AppError> {
+  let mut seen_buckets = HashSet::new();
+  for (created, version) in read_versions(dir)? {
+    let age = now - created;
+    let bucket = if age < Duration::hours(KEEP_ALL_HOURS) {
+      Some(version.clone())
+    } else if age < Duration::days(HOURLY_DAYS) {
+      Some(created.format("%Y%m%d-%H").to_string())
+    } else if age < Duration::days(DAILY_DAYS) {
+      Some(created.format("%Y%m%d").to_string())
+    } else {
+      None
+    };
+
+    let keep = bucket
+      .map(|bucket| seen_buckets.insert(bucket))
+      .unwrap_or(false);
+    if !keep {
+      fs::remove_file(dir.join(format!("{}{}", version, SNAPSHOT_EXTENSION)))
+        .map_err(|error| AppError::io("Prune history failed", error))?;
+    }
+  }
+
+  Ok(())
+}
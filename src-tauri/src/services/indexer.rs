@@ -1,51 +1,53 @@
-use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 
+use crate::models::AppError;
 use crate::services::notes_fs::{self, NoteIndexEntry};
+use crate::services::{dates, links, outline, tasks, todo_txt};
 
-#[derive(Debug)]
-pub struct IndexerError {
-  details: String,
-}
-
-impl IndexerError {
-  pub fn new(details: impl Into<String>) -> Self {
-    Self {
-      details: details.into(),
-    }
-  }
-}
+// Bump when a table changes shape: the index is rebuildable, so an outdated
+// database is simply dropped and recreated.
+const SCHEMA_VERSION: i64 = 7;
+const INDEX_TABLES: [&str; 8] = [
+  "notes_fts",
+  "notes",
+  "note_tags",
+  "links",
+  "sections",
+  "sections_fts",
+  "tasks",
+  "todo_items",
+];
 
-impl fmt::Display for IndexerError {
-  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(formatter, "{}", self.details)
-  }
-}
-
-impl std::error::Error for IndexerError {}
-
-pub fn rebuild_index() -> Result<(), IndexerError> {
+pub fn rebuild_index() -> Result<(), AppError> {
   let conn = open_connection()?;
   ensure_schema(&conn)?;
-  conn
-    .execute("DELETE FROM notes_fts", [])
-    .map_err(|error| IndexerError::new(format!("Clear index failed: {}", error)))?;
+  for table in INDEX_TABLES {
+    conn
+      .execute(&format!("DELETE FROM {}", table), [])
+      .map_err(|error| AppError::index("Clear index failed", error))?;
+  }
 
-  let notes = notes_fs::list_note_entries()
-    .map_err(|error| IndexerError::new(format!("Read notes failed: {}", error)))?;
+  let notes = notes_fs::list_note_entries()?;
   for entry in notes {
     insert_note(&conn, &entry)?;
   }
 
+  for file in [todo_txt::TODO_FILE, todo_txt::DONE_FILE] {
+    index_todo_file(&conn, file)?;
+  }
+
   Ok(())
 }
 
-pub fn upsert_note_by_path(path: &Path) -> Result<(), IndexerError> {
-  let entry = notes_fs::note_entry_from_path(path)
-    .map_err(|error| IndexerError::new(format!("Read note failed: {}", error)))?;
+pub fn upsert_note_by_path(path: &Path) -> Result<(), AppError> {
+  if let Some(file) = todo_txt::todo_file_name(path) {
+    return reindex_todo_file(&file);
+  }
+
+  let entry = notes_fs::note_entry_from_path(path)?;
   let Some(entry) = entry else {
     return Ok(());
   };
@@ -56,7 +58,11 @@ pub fn upsert_note_by_path(path: &Path) -> Result<(), IndexerError> {
   insert_note(&conn, &entry)
 }
 
-pub fn delete_note_by_path(path: &Path) -> Result<(), IndexerError> {
+pub fn delete_note_by_path(path: &Path) -> Result<(), AppError> {
+  if let Some(file) = todo_txt::todo_file_name(path) {
+    return reindex_todo_file(&file);
+  }
+
   let Some(id) = notes_fs::note_id_from_path(path) else {
     return Ok(());
   };
@@ -66,17 +72,79 @@ pub fn delete_note_by_path(path: &Path) -> Result<(), IndexerError> {
   delete_note_by_id(&conn, &id)
 }
 
-pub fn open_connection() -> Result<Connection, IndexerError> {
+pub fn reindex_note(id: &str) -> Result<(), AppError> {
+  let notes_dir = notes_fs::notes_dir()?;
+  upsert_note_by_path(&notes_dir.join(id))
+}
+
+pub fn remove_note(id: &str) -> Result<(), AppError> {
+  let conn = open_connection()?;
+  ensure_schema(&conn)?;
+  delete_note_by_id(&conn, id)
+}
+
+pub fn reindex_todo_file(file: &str) -> Result<(), AppError> {
+  let conn = open_connection()?;
+  ensure_schema(&conn)?;
+  index_todo_file(&conn, file)
+}
+
+// Commands accept either a file name or a frontmatter uid. File names win so
+// existing callers keep working; uids are looked up in the index first and
+// then on disk in case the index has not caught up with a rename yet.
+pub fn resolve_note_id(reference: &str) -> Result<String, AppError> {
+  let notes_dir = notes_fs::notes_dir()?;
+  if notes_fs::note_id_from_path(Path::new(reference)).is_some()
+    && notes_dir.join(reference).is_file()
+  {
+    return Ok(reference.to_string());
+  }
+
+  let conn = open_connection()?;
+  ensure_schema(&conn)?;
+  let indexed: Option<String> = conn
+    .query_row(
+      "SELECT id FROM notes WHERE uid = ?1 LIMIT 1",
+      params![reference],
+      |row| row.get(0),
+    )
+    .optional()
+    .map_err(|error| AppError::index("Uid lookup failed", error))?;
+  if let Some(id) = indexed {
+    if notes_dir.join(&id).is_file() {
+      return Ok(id);
+    }
+  }
+
+  let entries = notes_fs::list_note_entries()?;
+  entries
+    .into_iter()
+    .find(|entry| entry.metadata.uid.as_deref() == Some(reference))
+    .map(|entry| entry.metadata.id)
+    .ok_or_else(|| AppError::not_found("Note not found"))
+}
+
+pub fn open_connection() -> Result<Connection, AppError> {
   let path = db_path()?;
   if let Some(parent) = path.parent() {
-    fs::create_dir_all(parent)
-      .map_err(|error| IndexerError::new(format!("Create db dir failed: {}", error)))?;
+    fs::create_dir_all(parent).map_err(|error| AppError::io("Create db dir failed", error))?;
   }
 
-  Connection::open(path).map_err(|error| IndexerError::new(format!("Open db failed: {}", error)))
+  Connection::open(path).map_err(|error| AppError::index("Open db failed", error))
 }
 
-pub fn ensure_schema(conn: &Connection) -> Result<(), IndexerError> {
+pub fn ensure_schema(conn: &Connection) -> Result<(), AppError> {
+  let version: i64 = conn
+    .query_row("PRAGMA user_version", [], |row| row.get(0))
+    .map_err(|error| AppError::index("Read schema version failed", error))?;
+  if version != SCHEMA_VERSION {
+    for table in INDEX_TABLES {
+      conn
+        .execute(&format!("DROP TABLE IF EXISTS {}", table), [])
+        .map_err(|error| AppError::index("Drop outdated index failed", error))?;
+    }
+  }
+
   conn
     .execute_batch(
       "CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
@@ -90,21 +158,98 @@ pub fn ensure_schema(conn: &Connection) -> Result<(), IndexerError> {
         updated,
         archived,
         body
+      );
+      CREATE TABLE IF NOT EXISTS notes (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        layout TEXT NOT NULL,
+        date TEXT NOT NULL,
+        description TEXT NOT NULL,
+        statut TEXT NOT NULL,
+        tags TEXT NOT NULL,
+        updated TEXT NOT NULL,
+        archived INTEGER NOT NULL,
+        created_key TEXT NOT NULL,
+        updated_key TEXT NOT NULL,
+        uid TEXT,
+        invalid INTEGER NOT NULL
+      );
+      CREATE INDEX IF NOT EXISTS notes_uid ON notes (uid);
+      CREATE TABLE IF NOT EXISTS note_tags (
+        note_id TEXT NOT NULL,
+        tag TEXT NOT NULL COLLATE NOCASE,
+        PRIMARY KEY (note_id, tag)
+      );
+      CREATE INDEX IF NOT EXISTS note_tags_tag ON note_tags (tag);
+      CREATE TABLE IF NOT EXISTS links (
+        source_id TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        target TEXT NOT NULL,
+        anchor TEXT NOT NULL,
+        position INTEGER NOT NULL
+      );
+      CREATE INDEX IF NOT EXISTS links_source ON links (source_id);
+      CREATE INDEX IF NOT EXISTS links_target ON links (target COLLATE NOCASE);
+      CREATE TABLE IF NOT EXISTS sections (
+        note_id TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        level INTEGER NOT NULL,
+        heading TEXT NOT NULL,
+        start INTEGER NOT NULL,
+        end INTEGER NOT NULL,
+        line INTEGER NOT NULL,
+        PRIMARY KEY (note_id, position)
+      );
+      CREATE VIRTUAL TABLE IF NOT EXISTS sections_fts USING fts5(
+        note_id UNINDEXED,
+        position UNINDEXED,
+        heading,
+        content
+      );
+      CREATE TABLE IF NOT EXISTS tasks (
+        note_id TEXT NOT NULL,
+        line INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        done INTEGER NOT NULL,
+        due TEXT,
+        tags TEXT NOT NULL,
+        PRIMARY KEY (note_id, line)
+      );
+      CREATE INDEX IF NOT EXISTS tasks_due ON tasks (due);
+      CREATE TABLE IF NOT EXISTS todo_items (
+        file TEXT NOT NULL,
+        line INTEGER NOT NULL,
+        raw TEXT NOT NULL,
+        done INTEGER NOT NULL,
+        priority TEXT,
+        PRIMARY KEY (file, line)
       );",
     )
-    .map_err(|error| IndexerError::new(format!("Create schema failed: {}", error)))
+    .map_err(|error| AppError::index("Create schema failed", error))?;
+
+  if version != SCHEMA_VERSION {
+    conn
+      .execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+      .map_err(|error| AppError::index("Write schema version failed", error))?;
+  }
+
+  Ok(())
 }
 
-fn db_path() -> Result<PathBuf, IndexerError> {
-  let home = std::env::var("HOME").map_err(|_| IndexerError::new("HOME not set"))?;
+// Local application data (index, history): never inside the synced folder.
+pub fn data_dir() -> Result<PathBuf, AppError> {
+  let home = std::env::var("HOME").map_err(|error| AppError::internal("HOME not set", error))?;
   Ok(PathBuf::from(home)
     .join(".local")
     .join("share")
-    .join("velocitext")
-    .join("velocitext.db"))
+    .join("velocitext"))
 }
 
-fn insert_note(conn: &Connection, entry: &NoteIndexEntry) -> Result<(), IndexerError> {
+fn db_path() -> Result<PathBuf, AppError> {
+  Ok(data_dir()?.join("velocitext.db"))
+}
+
+fn insert_note(conn: &Connection, entry: &NoteIndexEntry) -> Result<(), AppError> {
   let tags = entry.metadata.tags.join(", ");
   let archived = if entry.metadata.archived { "true" } else { "false" };
 
@@ -119,20 +264,161 @@ fn insert_note(conn: &Connection, entry: &NoteIndexEntry) -> Result<(), IndexerE
         &entry.metadata.date,
         &entry.metadata.description,
         &entry.metadata.statut,
-        tags,
+        &tags,
         &entry.metadata.updated,
         archived,
         &entry.body
       ],
     )
-    .map_err(|error| IndexerError::new(format!("Insert note failed: {}", error)))?;
+    .map_err(|error| AppError::index("Insert note failed", error))?;
+
+  // Sort keys are stored in a lexically sortable form so list queries can
+  // order by real dates whatever format each note uses.
+  let created_key = dates::date_key(&entry.metadata.date);
+  let updated_key = dates::updated_key(&entry.metadata.updated);
+
+  conn
+    .execute(
+      "INSERT OR REPLACE INTO notes (id, title, layout, date, description, statut, tags, updated, archived, created_key, updated_key, uid, invalid)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+      params![
+        &entry.metadata.id,
+        &entry.metadata.title,
+        &entry.metadata.layout,
+        &entry.metadata.date,
+        &entry.metadata.description,
+        &entry.metadata.statut,
+        &tags,
+        &entry.metadata.updated,
+        entry.metadata.archived,
+        created_key,
+        updated_key,
+        &entry.metadata.uid,
+        entry.metadata.invalid
+      ],
+    )
+    .map_err(|error| AppError::index("Insert note failed", error))?;
+
+  for tag in &entry.metadata.tags {
+    conn
+      .execute(
+        "INSERT OR IGNORE INTO note_tags (note_id, tag) VALUES (?1, ?2)",
+        params![&entry.metadata.id, tag],
+      )
+      .map_err(|error| AppError::index("Insert tag failed", error))?;
+  }
+
+  for link in links::parse_links(&entry.body) {
+    conn
+      .execute(
+        "INSERT INTO links (source_id, kind, target, anchor, position) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+          &entry.metadata.id,
+          links::kind_name(&link.kind),
+          &link.target,
+          &link.anchor,
+          link.position as i64
+        ],
+      )
+      .map_err(|error| AppError::index("Insert link failed", error))?;
+  }
+
+  for (position, parsed) in outline::parse_sections(&entry.body).iter().enumerate() {
+    let section = &parsed.section;
+    conn
+      .execute(
+        "INSERT INTO sections (note_id, position, level, heading, start, end, line)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+          &entry.metadata.id,
+          position as i64,
+          section.level,
+          &section.heading,
+          (section.start + entry.body_offset) as i64,
+          (section.end + entry.body_offset) as i64,
+          (section.line + entry.body_line) as i64
+        ],
+      )
+      .map_err(|error| AppError::index("Insert section failed", error))?;
+    conn
+      .execute(
+        "INSERT INTO sections_fts (note_id, position, heading, content) VALUES (?1, ?2, ?3, ?4)",
+        params![
+          &entry.metadata.id,
+          position as i64,
+          &section.heading,
+          &parsed.content
+        ],
+      )
+      .map_err(|error| AppError::index("Insert section failed", error))?;
+  }
+
+  for task in tasks::parse_tasks(&entry.body) {
+    conn
+      .execute(
+        "INSERT INTO tasks (note_id, line, text, done, due, tags) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+          &entry.metadata.id,
+          task.line as i64,
+          &task.text,
+          task.done,
+          &task.due,
+          task.tags.join(", ")
+        ],
+      )
+      .map_err(|error| AppError::index("Insert task failed", error))?;
+  }
+
+  Ok(())
+}
+
+// Only the raw line is stored: listing parses it again, so the todo.txt
+// parser stays the single source of truth for the format.
+fn index_todo_file(conn: &Connection, file: &str) -> Result<(), AppError> {
+  conn
+    .execute("DELETE FROM todo_items WHERE file = ?1", params![file])
+    .map_err(|error| AppError::index("Clear todo items failed", error))?;
+
+  let items = todo_txt::read_todo_file(file)?;
+  for item in items {
+    conn
+      .execute(
+        "INSERT INTO todo_items (file, line, raw, done, priority) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+          &item.file,
+          item.line as i64,
+          &item.raw,
+          item.done,
+          &item.priority
+        ],
+      )
+      .map_err(|error| AppError::index("Insert todo item failed", error))?;
+  }
 
   Ok(())
 }
 
-fn delete_note_by_id(conn: &Connection, id: &str) -> Result<(), IndexerError> {
+fn delete_note_by_id(conn: &Connection, id: &str) -> Result<(), AppError> {
   conn
     .execute("DELETE FROM notes_fts WHERE id = ?1", params![id])
-    .map_err(|error| IndexerError::new(format!("Delete note failed: {}", error)))?;
+    .map_err(|error| AppError::index("Delete note failed", error))?;
+  conn
+    .execute("DELETE FROM notes WHERE id = ?1", params![id])
+    .map_err(|error| AppError::index("Delete note failed", error))?;
+  conn
+    .execute("DELETE FROM note_tags WHERE note_id = ?1", params![id])
+    .map_err(|error| AppError::index("Delete note failed", error))?;
+  conn
+    .execute("DELETE FROM links WHERE source_id = ?1", params![id])
+    .map_err(|error| AppError::index("Delete note failed", error))?;
+  conn
+    .execute("DELETE FROM sections WHERE note_id = ?1", params![id])
+    .map_err(|error| AppError::index("Delete note failed", error))?;
+  conn
+    .execute("DELETE FROM sections_fts WHERE note_id = ?1", params![id])
+    .map_err(|error| AppError::index("Delete note failed", error))?;
+  conn
+    .execute("DELETE FROM tasks WHERE note_id = ?1", params![id])
+    .map_err(|error| AppError::index("Delete note failed", error))?;
   Ok(())
 }
@@ -0,0 +1,296 @@
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::models::{AppError, LinkKind, NoteLink};
+use crate::services::{indexer, notes_fs};
+
+const WIKI_LINK_PATTERN: &str = r"\[\[([^\[\]|#]+)(#[^\[\]|]*)?(?:\|([^\[\]]+))?\]\]";
+const MARKDOWN_LINK_PATTERN: &str = r#"\[([^\[\]]*)\]\(\s*<?([^()\s<>]+)>?(?:\s+"[^"]*")?\s*\)"#;
+
+pub struct ParsedLink {
+  pub kind: LinkKind,
+  pub target: String,
+  pub anchor: String,
+  pub position: usize,
+}
+
+// Wiki links resolve by title, Markdown links by file name; both are stored
+// unresolved and joined against the notes table when queried, so a link to a
+// note created later starts resolving without reindexing its source.
+const LINK_SELECT: &str =
+  "SELECT l.source_id, src.title, l.kind, l.target, dst.id, l.anchor, l.position
+  FROM links l
+  JOIN notes src ON src.id = l.source_id
+  LEFT JOIN notes dst ON dst.id = (
+    SELECT n.id FROM notes n
+    WHERE (l.kind = 'wiki' AND (n.title = l.target COLLATE NOCASE OR n.uid = l.target))
+       OR (l.kind = 'markdown' AND n.id = l.target)
+    ORDER BY n.id
+    LIMIT 1
+  )";
+
+pub fn parse_links(body: &str) -> Vec<ParsedLink> {
+  let mut links = Vec::new();
+
+  if let Ok(wiki) = Regex::new(WIKI_LINK_PATTERN) {
+    for caps in wiki.captures_iter(body) {
+      let whole = caps.get(0).map(|found| found.start()).unwrap_or_default();
+      let target = caps[1].trim().to_string();
+      if target.is_empty() {
+        continue;
+      }
+      let anchor = caps
+        .get(3)
+        .map(|alias| alias.as_str().trim().to_string())
+        .unwrap_or_else(|| target.clone());
+      links.push(ParsedLink {
+        kind: LinkKind::Wiki,
+        target,
+        anchor,
+        position: whole,
+      });
+    }
+  }
+
+  if let Ok(markdown) = Regex::new(MARKDOWN_LINK_PATTERN) {
+    for caps in markdown.captures_iter(body) {
+      let start = caps.get(0).map(|found| found.start()).unwrap_or_default();
+      if body[..start].ends_with('!') {
+        continue;
+      }
+      let Some(target) = note_file_target(&caps[2]) else {
+        continue;
+      };
+      links.push(ParsedLink {
+        kind: LinkKind::Markdown,
+        target,
+        anchor: caps[1].trim().to_string(),
+        position: start,
+      });
+    }
+  }
+
+  links.sort_by_key(|link| link.position);
+  links
+}
+
+pub fn kind_name(kind: &LinkKind) -> &'static str {
+  match kind {
+    LinkKind::Wiki => "wiki",
+    LinkKind::Markdown => "markdown",
+  }
+}
+
+pub fn get_outgoing_links(id: &str) -> Result<Vec<NoteLink>, AppError> {
+  let conn = open()?;
+  query_links(
+    &conn,
+    &format!("{} WHERE l.source_id = ?1 ORDER BY l.position", LINK_SELECT),
+    id,
+  )
+}
+
+pub fn list_all_links() -> Result<Vec<NoteLink>, AppError> {
+  let conn = open()?;
+  let mut statement = conn
+    .prepare(&format!("{} ORDER BY l.source_id, l.position", LINK_SELECT))
+    .map_err(|error| AppError::index("Prepare links failed", error))?;
+  let rows = statement
+    .query_map([], link_from_row)
+    .map_err(|error| AppError::index("Links query failed", error))?;
+
+  let mut links = Vec::new();
+  for link in rows {
+    links.push(link.map_err(|error| AppError::index("Row error", error))?);
+  }
+  Ok(links)
+}
+
+pub fn get_backlinks(id: &str) -> Result<Vec<NoteLink>, AppError> {
+  let conn = open()?;
+  query_links(
+    &conn,
+    &format!(
+      "{} WHERE l.source_id != ?1 AND (
+         (l.kind = 'markdown' AND l.target = ?1)
+         OR (l.kind = 'wiki' AND l.target = (SELECT title FROM notes WHERE id = ?1) COLLATE NOCASE)
+         OR (l.kind = 'wiki' AND l.target = (SELECT uid FROM notes WHERE id = ?1))
+       )
+       ORDER BY src.title, l.position",
+      LINK_SELECT
+    ),
+    id,
+  )
+}
+
+// Rewrites links pointing at a renamed note in every other note that uses
+// them. Both ids must still be in the index when this runs, so the old title
+// can be looked up. Returns the ids of the notes that were rewritten.
+pub fn relink_renamed_note(old_id: &str, new_id: &str) -> Result<Vec<String>, AppError> {
+  let conn = open()?;
+  let old_title = note_title(&conn, old_id)?;
+  let new_title = note_title(&conn, new_id)?;
+  let retitled = match (&old_title, &new_title) {
+    (Some(old), Some(new)) if old != new => Some((old.as_str(), new.as_str())),
+    _ => None,
+  };
+
+  let mut statement = conn
+    .prepare(
+      "SELECT DISTINCT source_id FROM links
+       WHERE source_id != ?1 AND source_id != ?2 AND (
+         (kind = 'markdown' AND target = ?1)
+         OR (kind = 'wiki' AND ?3 IS NOT NULL AND target = ?3 COLLATE NOCASE)
+       )",
+    )
+    .map_err(|error| AppError::index("Prepare links failed", error))?;
+  let rows = statement
+    .query_map(
+      params![old_id, new_id, retitled.map(|(old, _)| old)],
+      |row| row.get::<_, String>(0),
+    )
+    .map_err(|error| AppError::index("Links query failed", error))?;
+
+  let mut sources = Vec::new();
+  for source in rows {
+    sources.push(source.map_err(|error| AppError::index("Row error", error))?);
+  }
+
+  let mut relinked = Vec::new();
+  for source in sources {
+    // Only the links change: `updated` is not bumped and the header is left
+    // as written. Notes still in another encoding wait for their "Convert to
+    // UTF-8" fix rather than being converted here.
+    if notes_fs::note_encoding(&source)?.is_some() {
+      continue;
+    }
+    let detail = notes_fs::get_note(&source)?;
+    let rewritten = rewrite_links(&detail.content, old_id, new_id, retitled);
+    if rewritten == detail.content {
+      continue;
+    }
+
+    notes_fs::replace_note_content(&source, &rewritten).map_err(|error| {
+      AppError::with_details(error.code, format!("Relink {} failed", source), error)
+    })?;
+    indexer::reindex_note(&source)?;
+    relinked.push(source);
+  }
+
+  Ok(relinked)
+}
+
+fn rewrite_links(
+  content: &str,
+  old_id: &str,
+  new_id: &str,
+  retitled: Option<(&str, &str)>,
+) -> String {
+  let mut rewritten = content.to_string();
+
+  if let Ok(markdown) = Regex::new(MARKDOWN_LINK_PATTERN) {
+    rewritten = markdown
+      .replace_all(&rewritten, |caps: &regex::Captures<'_>| {
+        let (Some(whole), Some(href)) = (caps.get(0), caps.get(2)) else {
+          return caps[0].to_string();
+        };
+        if note_file_target(href.as_str()).as_deref() != Some(old_id) {
+          return whole.as_str().to_string();
+        }
+        let start = href.start() - whole.start();
+        let end = href.end() - whole.start();
+        format!(
+          "{}{}{}",
+          &whole.as_str()[..start],
+          href.as_str().replacen(old_id, new_id, 1),
+          &whole.as_str()[end..]
+        )
+      })
+      .into_owned();
+  }
+
+  if let Some((old_title, new_title)) = retitled {
+    if let Ok(wiki) = Regex::new(WIKI_LINK_PATTERN) {
+      rewritten = wiki
+        .replace_all(&rewritten, |caps: &regex::Captures<'_>| {
+          if caps[1].trim().to_lowercase() != old_title.to_lowercase() {
+            return caps[0].to_string();
+          }
+          let fragment = caps.get(2).map(|found| found.as_str()).unwrap_or_default();
+          match caps.get(3) {
+            Some(alias) => format!("[[{}{}|{}]]", new_title, fragment, alias.as_str()),
+            None => format!("[[{}{}]]", new_title, fragment),
+          }
+        })
+        .into_owned();
+    }
+  }
+
+  rewritten
+}
+
+fn note_title(conn: &Connection, id: &str) -> Result<Option<String>, AppError> {
+  conn
+    .query_row(
+      "SELECT title FROM notes WHERE id = ?1",
+      params![id],
+      |row| row.get(0),
+    )
+    .optional()
+    .map_err(|error| AppError::index("Title lookup failed", error))
+}
+
+fn open() -> Result<Connection, AppError> {
+  let conn = indexer::open_connection()?;
+  indexer::ensure_schema(&conn)?;
+  Ok(conn)
+}
+
+fn query_links(conn: &Connection, sql: &str, id: &str) -> Result<Vec<NoteLink>, AppError> {
+  let mut statement = conn
+    .prepare(sql)
+    .map_err(|error| AppError::index("Prepare links failed", error))?;
+  let rows = statement
+    .query_map(params![id], link_from_row)
+    .map_err(|error| AppError::index("Links query failed", error))?;
+
+  let mut links = Vec::new();
+  for link in rows {
+    links.push(link.map_err(|error| AppError::index("Row error", error))?);
+  }
+  Ok(links)
+}
+
+fn link_from_row(row: &Row<'_>) -> rusqlite::Result<NoteLink> {
+  let kind: String = row.get(2)?;
+  let position: i64 = row.get(6)?;
+  Ok(NoteLink {
+    source_id: row.get(0)?,
+    source_title: row.get(1)?,
+    kind: if kind == "wiki" {
+      LinkKind::Wiki
+    } else {
+      LinkKind::Markdown
+    },
+    target: row.get(3)?,
+    target_id: row.get(4)?,
+    anchor: row.get(5)?,
+    position: position as usize,
+  })
+}
+
+// Notes live side by side in one folder, so only the file name of a relative
+// `.md` link matters; URLs and other files are not note links.
+fn note_file_target(href: &str) -> Option<String> {
+  if href.contains("://") || href.starts_with("mailto:") {
+    return None;
+  }
+
+  let path = href.split('#').next().unwrap_or_default();
+  let file_name = path.rsplit('/').next().unwrap_or_default();
+  if !file_name.ends_with(".md") || file_name.len() <= ".md".len() {
+    return None;
+  }
+
+  Some(file_name.replace("%20", " "))
+}
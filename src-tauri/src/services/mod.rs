@@ -1,4 +1,19 @@
+pub mod daily_notes;
+pub mod dates;
+pub mod diagnostics;
+pub mod drafts;
+pub mod git_history;
+pub mod graph;
+pub mod history;
 pub mod indexer;
+pub mod links;
 pub mod notes_fs;
+pub mod outline;
+pub mod saved_searches;
 pub mod search;
+pub mod stversions;
+pub mod tasks;
+pub mod templates;
+pub mod todo_txt;
+pub mod trash;
 pub mod watcher;
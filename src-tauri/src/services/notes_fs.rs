@@ -1,39 +1,42 @@
-use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use chrono::{Local, NaiveDate};
+use chardetng::EncodingDetector;
+use chrono::{Local, NaiveDateTime};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
 use regex::Regex;
 use serde::Deserialize;
+use uuid::Uuid;
 
-use crate::models::{NoteDetail, NoteMetadata};
+use crate::models::{
+  AppError, DiagnosticSeverity, ErrorCode, NoteDetail, NoteDiagnostic, NoteMetadata,
+};
+use crate::services::dates;
+use crate::services::diagnostics::{
+  check_frontmatter, encoding_error, has_errors, syntax_error, yaml_error,
+};
 
 const MAX_SLUG_LEN: usize = 101;
-const FORBIDDEN_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
-const DEFAULT_LAYOUT: &str = "note.njk";
-const DEFAULT_STATUT: &str = "idee";
-const STATUTS: [&str; 3] = ["idee", "chantier", "termine"];
-
-#[derive(Debug)]
-pub struct NotesError {
-  details: String,
-}
-
-impl NotesError {
-  pub fn new(details: impl Into<String>) -> Self {
-    Self {
-      details: details.into(),
-    }
-  }
-}
-
-impl fmt::Display for NotesError {
-  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(formatter, "{}", self.details)
-  }
-}
-
-impl std::error::Error for NotesError {}
+pub const DEFAULT_LAYOUT: &str = "note.njk";
+pub const DEFAULT_STATUT: &str = "idee";
+pub const STATUTS: [&str; 3] = ["idee", "chantier", "termine"];
+// Header keys in the order the standard header writes them.
+const HEADER_FIELDS: [&str; 9] = [
+  "title",
+  "layout",
+  "date",
+  "description",
+  "statut",
+  "tags",
+  "updated",
+  "archived",
+  "uid",
+];
+const BOM: char = '\u{feff}';
+// Like Git, a NUL byte this early means the file is binary.
+const BINARY_SNIFF_LEN: usize = 8000;
+// Syncthing's and Git's own entries inside the notes folder: never notes.
+const IGNORED_NAMES: [&str; 4] = [".stversions", ".stfolder", ".stignore", ".git"];
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 struct Frontmatter {
@@ -47,20 +50,146 @@ struct Frontmatter {
   tags: Vec<String>,
   updated: String,
   archived: bool,
+  #[serde(default)]
+  uid: Option<String>,
 }
 
 pub struct NoteIndexEntry {
   pub metadata: NoteMetadata,
   pub body: String,
+  // Where the body starts in the file (BOM and header included), as a byte
+  // offset and as the number of lines before it.
+  pub body_offset: usize,
+  pub body_line: usize,
+}
+
+pub struct NewNote {
+  pub title: String,
+  pub created: NaiveDateTime,
+  pub description: String,
+  pub statut: Option<String>,
+  pub tags: Vec<String>,
+  pub body: String,
+}
+
+impl NewNote {
+  pub fn new(title: &str, created: NaiveDateTime) -> Self {
+    Self {
+      title: title.to_string(),
+      created,
+      description: String::new(),
+      statut: None,
+      tags: Vec::new(),
+      body: String::new(),
+    }
+  }
 }
 
 struct ParsedNote {
   frontmatter: Frontmatter,
+  // The header as written, between the `---` lines.
+  yaml: String,
+  // Everything after the closing `---` line, byte for byte.
   body: String,
   raw: String,
+  style: TextStyle,
+  diagnostics: Vec<NoteDiagnostic>,
+}
+
+// The BOM and line endings a file was written with. Saves reproduce them so
+// notes edited on other platforms don't show up as changed everywhere.
+// `encoding` is set when the file was decoded from something other than
+// UTF-8.
+#[derive(Debug, Clone, Copy, Default)]
+struct TextStyle {
+  bom: bool,
+  crlf: bool,
+  encoding: Option<&'static Encoding>,
+}
+
+impl TextStyle {
+  // Line endings are taken from the first line break.
+  fn detect(content: &str) -> Self {
+    Self {
+      bom: content.starts_with(BOM),
+      crlf: content
+        .find('\n')
+        .map(|index| content[..index].ends_with('\r'))
+        .unwrap_or(false),
+      encoding: None,
+    }
+  }
+
+  // Only bare `\n` are converted, so the CRLF lines of an LF file (or the
+  // other way round) are left as they are.
+  fn apply(self, content: &str) -> String {
+    let content = content.strip_prefix(BOM).unwrap_or(content);
+    let mut styled = String::with_capacity(content.len() + 3);
+    if self.bom {
+      styled.push(BOM);
+    }
+    if self.crlf {
+      styled.push_str(&content.replace("\r\n", "\n").replace('\n', "\r\n"));
+    } else {
+      styled.push_str(content);
+    }
+    styled
+  }
+
+  // Files in another encoding are written back in it until the user applies
+  // the "Convert to UTF-8" fix. UTF-16 was recognised by its BOM, so it gets
+  // one back; encoding_rs only encodes to legacy single- and multi-byte
+  // encodings.
+  fn encode(self, content: &str) -> Result<Vec<u8>, AppError> {
+    let Some(encoding) = self.encoding else {
+      return Ok(content.as_bytes().to_vec());
+    };
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+      let mut bytes = Vec::with_capacity(content.len() * 2 + 2);
+      for unit in std::iter::once(0xfeff).chain(content.encode_utf16()) {
+        if encoding == UTF_16LE {
+          bytes.extend_from_slice(&unit.to_le_bytes());
+        } else {
+          bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+      }
+      return Ok(bytes);
+    }
+
+    let (bytes, _, unmappable) = encoding.encode(content);
+    if unmappable {
+      return Err(AppError::invalid_input(format!(
+        "The note has characters {} cannot store; apply \"Convert to UTF-8\" first",
+        encoding.name()
+      )));
+    }
+    Ok(bytes.into_owned())
+  }
+
+  // The encoding diagnostic stays until the file is converted.
+  fn diagnostics(self, mut diagnostics: Vec<NoteDiagnostic>) -> Vec<NoteDiagnostic> {
+    if let Some(encoding) = self.encoding {
+      diagnostics.insert(0, encoding_error(encoding.name()));
+    }
+    diagnostics
+  }
+}
+
+pub fn is_ignored_path(path: &Path) -> bool {
+  path.components().any(|component| {
+    component
+      .as_os_str()
+      .to_str()
+      .map(|name| IGNORED_NAMES.contains(&name))
+      .unwrap_or(false)
+  })
 }
 
 pub fn note_id_from_path(path: &Path) -> Option<String> {
+  if is_ignored_path(path) {
+    return None;
+  }
+
   if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
     return None;
   }
@@ -71,7 +200,7 @@ pub fn note_id_from_path(path: &Path) -> Option<String> {
     .map(str::to_string)
 }
 
-pub fn note_entry_from_path(path: &Path) -> Result<Option<NoteIndexEntry>, NotesError> {
+pub fn note_entry_from_path(path: &Path) -> Result<Option<NoteIndexEntry>, AppError> {
   if !path.exists() {
     return Ok(None);
   }
@@ -81,138 +210,143 @@ pub fn note_entry_from_path(path: &Path) -> Result<Option<NoteIndexEntry>, Notes
     None => return Ok(None),
   };
 
-  let parsed = read_note_file(path)?;
-  Ok(Some(NoteIndexEntry {
-    metadata: metadata_from_frontmatter(file_name, &parsed.frontmatter),
-    body: parsed.body,
-  }))
+  let Some(parsed) = read_note_file(path)? else {
+    return Ok(None);
+  };
+  Ok(Some(index_entry(file_name, parsed)))
 }
 
-pub fn list_notes() -> Result<Vec<NoteMetadata>, NotesError> {
+pub fn list_note_entries() -> Result<Vec<NoteIndexEntry>, AppError> {
   let notes_dir = notes_dir()?;
   if !notes_dir.exists() {
     return Ok(Vec::new());
   }
 
   let mut notes = Vec::new();
-  let entries = fs::read_dir(&notes_dir)
-    .map_err(|error| NotesError::new(format!("Cannot read notes dir: {}", error)))?;
+  let entries =
+    fs::read_dir(&notes_dir).map_err(|error| AppError::io("Cannot read notes dir", error))?;
 
   for entry in entries {
-    let entry = entry.map_err(|error| NotesError::new(format!("Read entry failed: {}", error)))?;
+    let entry = entry.map_err(|error| AppError::io("Read entry failed", error))?;
     let path = entry.path();
-    if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+    if is_ignored_path(&path) || path.extension().and_then(|ext| ext.to_str()) != Some("md") {
       continue;
     }
 
-    let parsed = read_note_file(&path)?;
-    let file_name = file_name(&path)?;
-    notes.push(metadata_from_frontmatter(file_name, &parsed.frontmatter));
-  }
-
-  notes.sort_by(|left, right| {
-    let left_date = parse_date(&left.date);
-    let right_date = parse_date(&right.date);
-    right_date
-      .cmp(&left_date)
-      .then_with(|| right.title.cmp(&left.title))
-  });
-  Ok(notes)
-}
-
-pub fn list_note_entries() -> Result<Vec<NoteIndexEntry>, NotesError> {
-  let notes_dir = notes_dir()?;
-  if !notes_dir.exists() {
-    return Ok(Vec::new());
-  }
-
-  let mut notes = Vec::new();
-  let entries = fs::read_dir(&notes_dir)
-    .map_err(|error| NotesError::new(format!("Cannot read notes dir: {}", error)))?;
-
-  for entry in entries {
-    let entry = entry.map_err(|error| NotesError::new(format!("Read entry failed: {}", error)))?;
-    let path = entry.path();
-    if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+    // Binary files that only have a `.md` name are not notes.
+    let Some(parsed) = read_note_file(&path)? else {
       continue;
-    }
-
-    let parsed = read_note_file(&path)?;
+    };
     let file_name = file_name(&path)?;
-    notes.push(NoteIndexEntry {
-      metadata: metadata_from_frontmatter(file_name, &parsed.frontmatter),
-      body: parsed.body,
-    });
+    notes.push(index_entry(file_name, parsed));
   }
 
   Ok(notes)
 }
 
-pub fn get_note(id: &str) -> Result<NoteDetail, NotesError> {
+pub fn get_note(id: &str) -> Result<NoteDetail, AppError> {
   let notes_dir = notes_dir()?;
   let path = safe_note_path(&notes_dir, id)?;
   if !path.exists() {
-    return Err(NotesError::new("Note not found"));
+    return Err(AppError::not_found("Note not found"));
   }
 
-  let parsed = read_note_file(&path)?;
+  let parsed = read_text_note(&path)?;
   Ok(NoteDetail {
-    metadata: metadata_from_frontmatter(file_name(&path)?, &parsed.frontmatter),
+    metadata: note_metadata(file_name(&path)?, &parsed),
     content: parsed.raw,
+    diagnostics: parsed.diagnostics,
   })
 }
 
-pub fn create_note(title: &str) -> Result<NoteDetail, NotesError> {
+pub fn create_note(title: &str) -> Result<NoteDetail, AppError> {
+  create_note_with(&NewNote::new(title, Local::now().naive_local()))
+}
+
+// Creates a note dated `created` (which also sets the file name prefix) with
+// an initial body and frontmatter defaults, e.g. from a template.
+pub fn create_note_with(note: &NewNote) -> Result<NoteDetail, AppError> {
+  let statut = note.statut.as_deref().unwrap_or(DEFAULT_STATUT);
+  if !STATUTS.contains(&statut) {
+    return Err(AppError::invalid_input("Invalid statut"));
+  }
+
   let notes_dir = ensure_notes_dir()?;
-  let now = Local::now();
-  let date = now.format("%d-%m-%Y").to_string();
-  let time = now.format("%H-%M").to_string();
-  let updated = now.format("%d-%m-%Y %H:%M").to_string();
+  // The file name prefix keeps its own fixed format whatever the vault's.
+  let prefix = note.created.format("%d-%m-%Y_%H-%M--").to_string();
+  let date = dates::format_date(note.created.date(), dates::vault_format());
+  let updated = dates::now_updated();
 
-  let slug = slugify_title(title);
-  let prefix = format!("{}_{}--", date, time);
+  let slug = slugify_title(&note.title);
   let base_name = build_filename(&prefix, &slug);
   let file_name = ensure_unique_filename(&notes_dir, &base_name, None);
   let frontmatter = Frontmatter {
-    title: title.to_string(),
+    title: note.title.clone(),
     layout: DEFAULT_LAYOUT.to_string(),
     date,
-    description: String::new(),
-    statut: DEFAULT_STATUT.to_string(),
-    tags: Vec::new(),
+    description: note.description.clone(),
+    statut: statut.to_string(),
+    tags: note.tags.clone(),
     updated,
     archived: false,
+    uid: Some(Uuid::new_v4().to_string()),
   };
 
-  let content = build_content(&frontmatter, "");
+  let body = if note.body.is_empty() {
+    String::new()
+  } else {
+    format!("\n{}", note.body)
+  };
+  let content = build_content(&format_frontmatter(&frontmatter), &body);
   let path = notes_dir.join(&file_name);
-  fs::write(&path, &content)
-    .map_err(|error| NotesError::new(format!("Write file failed: {}", error)))?;
+  fs::write(&path, &content).map_err(|error| AppError::io("Write file failed", error))?;
 
   Ok(NoteDetail {
     metadata: metadata_from_frontmatter(file_name, &frontmatter),
     content,
+    diagnostics: Vec::new(),
   })
 }
 
-pub fn save_note(id: &str, content: &str) -> Result<NoteDetail, NotesError> {
+pub fn save_note(id: &str, content: &str) -> Result<NoteDetail, AppError> {
   let notes_dir = ensure_notes_dir()?;
   let path = safe_note_path(&notes_dir, id)?;
   if !path.exists() {
-    return Err(NotesError::new("Note not found"));
+    return Err(AppError::not_found("Note not found"));
+  }
+
+  let existing = read_text_note(&path)?;
+  let incoming = parse_note(id, content.to_string());
+  // Mid-edit YAML is written as typed so autosave never loses text; the
+  // note keeps its file name and stays flagged until the header is fixed.
+  if has_errors(&incoming.diagnostics) {
+    let content = existing.style.apply(content);
+    let bytes = existing.style.encode(&content)?;
+    fs::write(&path, bytes).map_err(|error| AppError::io("Write file failed", error))?;
+    return Ok(NoteDetail {
+      metadata: note_metadata(id.to_string(), &incoming),
+      content,
+      diagnostics: existing.style.diagnostics(incoming.diagnostics),
+    });
   }
 
-  let existing = read_note_file(&path)?;
-  let (mut frontmatter, body) = parse_frontmatter(content)?;
+  let mut frontmatter = incoming.frontmatter.clone();
+  let body = incoming.body;
+  // The uid is the note's identity across renames; keep it if the editor dropped it.
+  if frontmatter.uid.is_none() {
+    frontmatter.uid = existing.frontmatter.uid.clone();
+  }
 
   let mut existing_compare = existing.frontmatter.clone();
   existing_compare.updated.clear();
   let mut incoming_compare = frontmatter.clone();
   incoming_compare.updated.clear();
 
-  let content_changed = existing.body != body || existing_compare != incoming_compare;
+  // An editor that sends LF lines for a CRLF file has not changed the body.
+  let content_changed = existing.body.replace("\r\n", "\n") != body.replace("\r\n", "\n")
+    || existing_compare != incoming_compare;
   if content_changed && frontmatter.updated == existing.frontmatter.updated {
-    frontmatter.updated = Local::now().format("%d-%m-%Y %H:%M").to_string();
+    frontmatter.updated = dates::now_updated();
   }
 
   let prefix = extract_prefix(id).unwrap_or_else(current_prefix);
@@ -224,63 +358,143 @@ pub fn save_note(id: &str, content: &str) -> Result<NoteDetail, NotesError> {
     id.to_string()
   };
 
-  let normalized_content = build_content(&frontmatter, &body);
+  let header = render_frontmatter(&incoming.yaml, &incoming.frontmatter, &frontmatter);
+  let normalized_content = existing.style.apply(&build_content(&header, &body));
+  let bytes = existing.style.encode(&normalized_content)?;
   let target_path = notes_dir.join(&target_name);
-  fs::write(&target_path, &normalized_content)
-    .map_err(|error| NotesError::new(format!("Write file failed: {}", error)))?;
+  fs::write(&target_path, bytes).map_err(|error| AppError::io("Write file failed", error))?;
 
   if target_path != path {
-    fs::remove_file(&path)
-      .map_err(|error| NotesError::new(format!("Remove old file failed: {}", error)))?;
+    fs::remove_file(&path).map_err(|error| AppError::io("Remove old file failed", error))?;
   }
 
+  let diagnostics = existing
+    .style
+    .diagnostics(content_diagnostics(&target_name, &normalized_content));
+  let mut metadata = metadata_from_frontmatter(target_name, &frontmatter);
+  metadata.invalid = has_errors(&diagnostics);
   Ok(NoteDetail {
-    metadata: metadata_from_frontmatter(target_name, &frontmatter),
+    metadata,
     content: normalized_content,
+    diagnostics,
   })
 }
 
-pub fn set_archived(id: &str, archived: bool) -> Result<NoteDetail, NotesError> {
+pub fn set_archived(id: &str, archived: bool) -> Result<NoteDetail, AppError> {
   let notes_dir = ensure_notes_dir()?;
   let path = safe_note_path(&notes_dir, id)?;
   if !path.exists() {
-    return Err(NotesError::new("Note not found"));
+    return Err(AppError::not_found("Note not found"));
+  }
+
+  let existing = read_text_note(&path)?;
+  if let Some(diagnostic) = existing
+    .diagnostics
+    .iter()
+    .find(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+  {
+    return Err(AppError::new(
+      ErrorCode::InvalidFrontmatter,
+      diagnostic.message.clone(),
+    ));
   }
 
-  let existing = read_note_file(&path)?;
   let mut frontmatter = existing.frontmatter.clone();
   if frontmatter.archived == archived {
     return Ok(NoteDetail {
       metadata: metadata_from_frontmatter(id.to_string(), &frontmatter),
       content: existing.raw,
+      diagnostics: existing.diagnostics,
     });
   }
 
   frontmatter.archived = archived;
   if frontmatter.updated == existing.frontmatter.updated {
-    frontmatter.updated = Local::now().format("%d-%m-%Y %H:%M").to_string();
+    frontmatter.updated = dates::now_updated();
   }
 
-  let normalized_content = build_content(&frontmatter, &existing.body);
-  fs::write(&path, &normalized_content)
-    .map_err(|error| NotesError::new(format!("Write file failed: {}", error)))?;
+  let header = render_frontmatter(&existing.yaml, &existing.frontmatter, &frontmatter);
+  let normalized_content = existing
+    .style
+    .apply(&build_content(&header, &existing.body));
+  let bytes = existing.style.encode(&normalized_content)?;
+  fs::write(&path, bytes).map_err(|error| AppError::io("Write file failed", error))?;
 
   Ok(NoteDetail {
     metadata: metadata_from_frontmatter(id.to_string(), &frontmatter),
+    diagnostics: content_diagnostics(id, &normalized_content),
     content: normalized_content,
   })
 }
 
+// Note `raw` with the header fields in `fields` set as in `repaired`, a
+// repair of it: only their lines change, the rest of the header and the
+// body stay as written. While `repaired` still has errors it is taken as
+// is. Unlike save_note, `updated` is kept.
+pub fn repaired_content(id: &str, raw: &str, repaired: &str, fields: &[&str]) -> String {
+  let original = parse_note(id, raw.to_string());
+  let repaired = parse_note(id, repaired.to_string());
+  if has_errors(&repaired.diagnostics) {
+    return original.style.apply(&repaired.raw);
+  }
+  let header = patch_header(&original.yaml, &repaired.frontmatter, fields);
+  original
+    .style
+    .apply(&build_content(&header, &original.body))
+}
+
+// Writes `content` over the note as is, in UTF-8: no rename and no
+// `updated` bump.
+pub fn replace_note_content(id: &str, content: &str) -> Result<NoteDetail, AppError> {
+  let path = existing_note_path(id)?;
+  fs::write(&path, content).map_err(|error| AppError::io("Write file failed", error))?;
+
+  let parsed = parse_note(id, content.to_string());
+  Ok(NoteDetail {
+    metadata: note_metadata(id.to_string(), &parsed),
+    content: parsed.raw,
+    diagnostics: parsed.diagnostics,
+  })
+}
+
+// The name of the encoding the note is stored in, when it is not UTF-8.
+pub fn note_encoding(id: &str) -> Result<Option<&'static str>, AppError> {
+  let path = existing_note_path(id)?;
+  Ok(read_text_note(&path)?.style.encoding.map(Encoding::name))
+}
+
+// Validated path of an existing note, for services that move note files.
+pub fn existing_note_path(id: &str) -> Result<PathBuf, AppError> {
+  let path = safe_note_path(&notes_dir()?, id)?;
+  if !path.exists() {
+    return Err(AppError::not_found("Note not found"));
+  }
+  Ok(path)
+}
+
+// A file name for `id` that is free in the notes folder (`_2`, `_3`... on
+// collisions), for notes coming back from elsewhere.
+pub fn available_note_id(id: &str) -> Result<String, AppError> {
+  let notes_dir = ensure_notes_dir()?;
+  safe_note_path(&notes_dir, id)?;
+  Ok(ensure_unique_filename(&notes_dir, id, None))
+}
+
+// Note ids only allow ASCII letters, digits, `_` and `-`, so accented
+// letters lose their accent ("Réunion" gives "Reunion") and any other
+// character becomes `-`.
 pub fn slugify_title(title: &str) -> String {
   let mut slug = String::new();
 
   for ch in title.chars() {
     if ch == ' ' {
       slug.push('_');
-    } else if FORBIDDEN_CHARS.contains(&ch) {
-      slug.push('-');
-    } else {
+    } else if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
       slug.push(ch);
+    } else if let Some(ascii) = unaccented(ch) {
+      slug.push_str(ascii);
+    } else {
+      slug.push('-');
     }
 
     if slug.chars().count() >= MAX_SLUG_LEN {
@@ -295,41 +509,75 @@ pub fn slugify_title(title: &str) -> String {
   }
 }
 
-fn validate_note_id(id: &str) -> Result<(), NotesError> {
+fn unaccented(ch: char) -> Option<&'static str> {
+  let ascii = match ch {
+    'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => "a",
+    'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => "A",
+    'æ' => "ae",
+    'Æ' => "AE",
+    'ç' => "c",
+    'Ç' => "C",
+    'è' | 'é' | 'ê' | 'ë' => "e",
+    'È' | 'É' | 'Ê' | 'Ë' => "E",
+    'ì' | 'í' | 'î' | 'ï' => "i",
+    'Ì' | 'Í' | 'Î' | 'Ï' => "I",
+    'ñ' => "n",
+    'Ñ' => "N",
+    'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => "o",
+    'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => "O",
+    'œ' => "oe",
+    'Œ' => "OE",
+    'ù' | 'ú' | 'û' | 'ü' => "u",
+    'Ù' | 'Ú' | 'Û' | 'Ü' => "U",
+    'ý' | 'ÿ' => "y",
+    'Ý' | 'Ÿ' => "Y",
+    'ß' => "ss",
+    _ => return None,
+  };
+  Some(ascii)
+}
+
+fn validate_note_id(id: &str) -> Result<(), AppError> {
   // Ensure the ID is a valid filename without path separators
   if id.contains('/') || id.contains('\\') {
-    return Err(NotesError::new("Invalid note ID: contains path separators"));
+    return Err(AppError::invalid_id(
+      "Invalid note ID: contains path separators",
+    ));
   }
 
   // Ensure the ID doesn't contain relative path components
   if id.contains("..") {
-    return Err(NotesError::new("Invalid note ID: contains relative path"));
+    return Err(AppError::invalid_id(
+      "Invalid note ID: contains relative path",
+    ));
   }
 
   // Ensure the ID ends with .md
   if !id.ends_with(".md") {
-    return Err(NotesError::new("Invalid note ID: must be a markdown file"));
+    return Err(AppError::invalid_id(
+      "Invalid note ID: must be a markdown file",
+    ));
   }
 
   // Validate against expected pattern: DD-MM-YYYY_HH-MM--slug.md
   let note_pattern = Regex::new(r"^\d{2}-\d{2}-\d{4}_\d{2}-\d{2}--[a-zA-Z0-9_\-]+(?:_\d+)?\.md$")
-    .map_err(|_| NotesError::new("Regex compilation failed"))?;
+    .map_err(|error| AppError::internal("Regex compilation failed", error))?;
   
   if !note_pattern.is_match(id) {
-    return Err(NotesError::new("Invalid note ID format"));
+    return Err(AppError::invalid_id("Invalid note ID format"));
   }
 
   Ok(())
 }
 
-fn safe_note_path(notes_dir: &Path, id: &str) -> Result<PathBuf, NotesError> {
+fn safe_note_path(notes_dir: &Path, id: &str) -> Result<PathBuf, AppError> {
   validate_note_id(id)?;
   
   let path = notes_dir.join(id);
   
   // Ensure the resolved path is still within the notes directory
   let canonical_notes_dir = notes_dir.canonicalize()
-    .map_err(|_| NotesError::new("Cannot resolve notes directory"))?;
+    .map_err(|error| AppError::io("Cannot resolve notes directory", error))?;
   
   let canonical_path = path.canonicalize()
     .unwrap_or_else(|_| {
@@ -342,90 +590,224 @@ fn safe_note_path(notes_dir: &Path, id: &str) -> Result<PathBuf, NotesError> {
     });
 
   if !canonical_path.starts_with(&canonical_notes_dir) {
-    return Err(NotesError::new("Note path outside allowed directory"));
+    return Err(AppError::invalid_id("Note path outside allowed directory"));
   }
 
   Ok(path)
 }
 
-pub fn notes_dir() -> Result<PathBuf, NotesError> {
-  let home = std::env::var("HOME").map_err(|_| NotesError::new("HOME not set"))?;
+pub fn notes_dir() -> Result<PathBuf, AppError> {
+  let home = std::env::var("HOME").map_err(|error| AppError::internal("HOME not set", error))?;
   Ok(PathBuf::from(home).join("Notes").join("Velocitext"))
 }
 
-fn ensure_notes_dir() -> Result<PathBuf, NotesError> {
+pub fn vault_config_dir() -> Result<PathBuf, AppError> {
+  Ok(notes_dir()?.join(".velocitext"))
+}
+
+fn ensure_notes_dir() -> Result<PathBuf, AppError> {
   let dir = notes_dir()?;
   if !dir.exists() {
-    fs::create_dir_all(&dir)
-      .map_err(|error| NotesError::new(format!("Create notes dir failed: {}", error)))?;
+    fs::create_dir_all(&dir).map_err(|error| AppError::io("Create notes dir failed", error))?;
   }
   Ok(dir)
 }
 
-fn read_note_file(path: &Path) -> Result<ParsedNote, NotesError> {
-  let raw = fs::read_to_string(path)
-    .map_err(|error| NotesError::new(format!("Read file failed: {}", error)))?;
-  let (frontmatter, body) = parse_frontmatter(&raw)?;
-  Ok(ParsedNote {
-    frontmatter,
+// None for a binary file. Files in another encoding are decoded and flagged
+// with a diagnostic offering to convert them to UTF-8.
+fn read_note_file(path: &Path) -> Result<Option<ParsedNote>, AppError> {
+  let bytes = fs::read(path).map_err(|error| AppError::io("Read file failed", error))?;
+  let Some((raw, encoding)) = decode_note(bytes) else {
+    return Ok(None);
+  };
+
+  let mut parsed = parse_note(&file_name(path)?, raw);
+  parsed.style.encoding = encoding;
+  parsed.diagnostics = parsed.style.diagnostics(parsed.diagnostics);
+  Ok(Some(parsed))
+}
+
+fn read_text_note(path: &Path) -> Result<ParsedNote, AppError> {
+  read_note_file(path)?.ok_or_else(|| AppError::invalid_input("Not a text file"))
+}
+
+// The text and, when it is not UTF-8, the encoding it was decoded from:
+// UTF-16 when the file starts with its BOM, otherwise a guess such as
+// windows-1252 for Latin-1 files.
+fn decode_note(bytes: Vec<u8>) -> Option<(String, Option<&'static Encoding>)> {
+  if let Some((encoding, bom_len)) =
+    Encoding::for_bom(&bytes).filter(|(encoding, _)| *encoding != UTF_8)
+  {
+    let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+    return Some((text.into_owned(), Some(encoding)));
+  }
+
+  if bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+    return None;
+  }
+
+  match String::from_utf8(bytes) {
+    Ok(text) => Some((text, None)),
+    Err(error) => {
+      let bytes = error.into_bytes();
+      let mut detector = EncodingDetector::new();
+      detector.feed(&bytes, true);
+      let encoding = detector.guess(None, false);
+      let (text, _) = encoding.decode_without_bom_handling(&bytes);
+      Some((text.into_owned(), Some(encoding)))
+    }
+  }
+}
+
+// A note whose frontmatter has errors is still listed and indexed: its
+// metadata is recovered field by field where possible, with the file name
+// standing in for a missing title.
+fn parse_note(id: &str, raw: String) -> ParsedNote {
+  let (yaml, body) = match split_frontmatter(&raw) {
+    Ok(split) => split,
+    Err(diagnostic) => {
+      return ParsedNote {
+        frontmatter: recover_frontmatter(id, &serde_yaml::Value::Null),
+        yaml: String::new(),
+        body: raw.strip_prefix(BOM).unwrap_or(&raw).to_string(),
+        style: TextStyle::detect(&raw),
+        raw,
+        diagnostics: vec![diagnostic],
+      }
+    }
+  };
+
+  let mut diagnostics = check_frontmatter(&yaml, id);
+  if !has_errors(&diagnostics) {
+    match serde_yaml::from_str::<Frontmatter>(&yaml) {
+      Ok(frontmatter) => {
+        return ParsedNote {
+          frontmatter,
+          yaml,
+          body,
+          style: TextStyle::detect(&raw),
+          raw,
+          diagnostics,
+        }
+      }
+      Err(error) => diagnostics.push(yaml_error(&error)),
+    }
+  }
+
+  let value = serde_yaml::from_str(&yaml).unwrap_or(serde_yaml::Value::Null);
+  ParsedNote {
+    frontmatter: recover_frontmatter(id, &value),
+    yaml,
     body,
+    style: TextStyle::detect(&raw),
     raw,
-  })
+    diagnostics,
+  }
+}
+
+fn recover_frontmatter(id: &str, yaml: &serde_yaml::Value) -> Frontmatter {
+  let text = |key: &str| {
+    yaml
+      .get(key)
+      .and_then(|value| value.as_str())
+      .map(str::to_string)
+  };
+
+  Frontmatter {
+    title: text("title")
+      .filter(|title| !title.trim().is_empty())
+      .unwrap_or_else(|| title_from_id(id)),
+    layout: text("layout").unwrap_or_else(|| DEFAULT_LAYOUT.to_string()),
+    date: text("date")
+      .or_else(|| {
+        id.get(..10)
+          .and_then(dates::parse_date)
+          .map(|date| dates::format_date(date, dates::vault_format()))
+      })
+      .unwrap_or_default(),
+    description: text("description").unwrap_or_default(),
+    statut: text("statut").unwrap_or_else(|| DEFAULT_STATUT.to_string()),
+    tags: yaml
+      .get("tags")
+      .and_then(|tags| tags.as_sequence())
+      .map(|tags| {
+        tags
+          .iter()
+          .filter_map(|tag| tag.as_str().map(str::to_string))
+          .collect()
+      })
+      .unwrap_or_default(),
+    updated: text("updated").unwrap_or_default(),
+    archived: yaml
+      .get("archived")
+      .and_then(|archived| archived.as_bool())
+      .unwrap_or(false),
+    uid: text("uid"),
+  }
 }
 
-fn parse_frontmatter(content: &str) -> Result<(Frontmatter, String), NotesError> {
-  let mut lines = content.lines();
+fn content_diagnostics(id: &str, content: &str) -> Vec<NoteDiagnostic> {
+  match split_frontmatter(content) {
+    Ok((yaml, _)) => check_frontmatter(&yaml, id),
+    Err(diagnostic) => vec![diagnostic],
+  }
+}
+
+// The header lines joined with `\n`, and the body exactly as in `content`
+// (trailing newline and CRLF endings included). A leading BOM is skipped.
+pub fn split_frontmatter(content: &str) -> Result<(String, String), NoteDiagnostic> {
+  let content = content.strip_prefix(BOM).unwrap_or(content);
+  let mut lines = content.split_inclusive('\n');
   let first_line = lines
     .next()
-    .ok_or_else(|| NotesError::new("Empty file"))?;
+    .ok_or_else(|| syntax_error(1, 1, "Empty file"))?;
 
   if first_line.trim() != "---" {
-    return Err(NotesError::new("Missing frontmatter"));
+    return Err(syntax_error(1, 1, "Missing frontmatter"));
   }
 
   let mut yaml_lines = Vec::new();
   let mut found_end = false;
+  let mut body_start = first_line.len();
 
   for line in &mut lines {
+    body_start += line.len();
     if line.trim() == "---" {
       found_end = true;
       break;
     }
-    yaml_lines.push(line);
+    yaml_lines.push(line.trim_end_matches(['\n', '\r']));
   }
 
   if !found_end {
-    return Err(NotesError::new("Frontmatter not closed"));
+    return Err(syntax_error(1, 1, "Frontmatter not closed"));
   }
 
   let yaml = yaml_lines.join("\n");
-  let body = lines.collect::<Vec<_>>().join("\n");
-
-  let frontmatter: Frontmatter = serde_yaml::from_str(&yaml)
-    .map_err(|error| NotesError::new(format!("Invalid frontmatter: {}", error)))?;
-
-  validate_frontmatter(&frontmatter)?;
-  Ok((frontmatter, body))
+  Ok((yaml, content[body_start..].to_string()))
 }
 
-fn validate_frontmatter(frontmatter: &Frontmatter) -> Result<(), NotesError> {
-  if frontmatter.layout != DEFAULT_LAYOUT {
-    return Err(NotesError::new("Invalid layout"));
-  }
-
-  if !STATUTS.contains(&frontmatter.statut.as_str()) {
-    return Err(NotesError::new("Invalid statut"));
-  }
+pub fn title_from_id(id: &str) -> String {
+  let stem = id.strip_suffix(".md").unwrap_or(id);
+  let slug = stem.split_once("--").map(|(_, slug)| slug).unwrap_or(stem);
+  slug.replace('_', " ")
+}
 
-  if frontmatter.title.trim().is_empty() {
-    return Err(NotesError::new("Missing title"));
-  }
+fn note_metadata(id: String, parsed: &ParsedNote) -> NoteMetadata {
+  let mut metadata = metadata_from_frontmatter(id, &parsed.frontmatter);
+  metadata.invalid = has_errors(&parsed.diagnostics);
+  metadata
+}
 
-  if frontmatter.date.trim().is_empty() || frontmatter.updated.trim().is_empty() {
-    return Err(NotesError::new("Missing date fields"));
+// The body is always a suffix of the raw text.
+fn index_entry(id: String, parsed: ParsedNote) -> NoteIndexEntry {
+  let body_offset = parsed.raw.len() - parsed.body.len();
+  NoteIndexEntry {
+    metadata: note_metadata(id, &parsed),
+    body_line: parsed.raw[..body_offset].matches('\n').count(),
+    body: parsed.body,
+    body_offset,
   }
-
-  Ok(())
 }
 
 fn metadata_from_frontmatter(id: String, frontmatter: &Frontmatter) -> NoteMetadata {
@@ -439,49 +821,149 @@ fn metadata_from_frontmatter(id: String, frontmatter: &Frontmatter) -> NoteMetad
     tags: frontmatter.tags.clone(),
     updated: frontmatter.updated.clone(),
     archived: frontmatter.archived,
+    uid: frontmatter.uid.clone(),
+    invalid: false,
   }
 }
 
-fn build_content(frontmatter: &Frontmatter, body: &str) -> String {
-  let header = format_frontmatter(frontmatter);
-  if body.is_empty() {
-    header
-  } else {
-    format!("{}\n{}", header, body)
-  }
+// `header` ends with the closing `---` line; the body follows it directly.
+fn build_content(header: &str, body: &str) -> String {
+  format!("{}{}", header, body)
 }
 
+// The standard header. Every value is written so that it reads back as the
+// same string, whatever characters it holds.
 fn format_frontmatter(frontmatter: &Frontmatter) -> String {
-  let title = escape_yaml_string(&frontmatter.title);
-  let description = escape_yaml_string(&frontmatter.description);
-  let tags = if frontmatter.tags.is_empty() {
-    "tags: []\n".to_string()
+  let mut lines = Vec::new();
+  for key in HEADER_FIELDS {
+    if key == "tags" {
+      if frontmatter.tags.is_empty() {
+        lines.push("tags: []".to_string());
+      } else {
+        lines.push("tags:".to_string());
+        for tag in &frontmatter.tags {
+          lines.push(format!("  - {}", plain_or_quoted(tag)));
+        }
+      }
+    } else if let Some(value) = field_value(frontmatter, key) {
+      lines.push(format!("{}: {}", key, value));
+    }
+  }
+
+  format!("---\n{}\n---\n", lines.join("\n"))
+}
+
+// Writes `frontmatter` over the header the user wrote (`yaml`, which read
+// as `original`), replacing only the lines of fields whose value changed so
+// their own quoting, order and comments stay. Falls back to the standard
+// header when a changed field spans several lines or the result would not
+// read back as `frontmatter`.
+fn render_frontmatter(yaml: &str, original: &Frontmatter, frontmatter: &Frontmatter) -> String {
+  let changed: Vec<&str> = HEADER_FIELDS
+    .into_iter()
+    .filter(|key| match *key {
+      "tags" => original.tags != frontmatter.tags,
+      _ => field_value(frontmatter, key) != field_value(original, key),
+    })
+    .collect();
+  patch_header(yaml, frontmatter, &changed)
+}
+
+// `yaml` with the lines of the `changed` fields set from `frontmatter`.
+fn patch_header(yaml: &str, frontmatter: &Frontmatter, changed: &[&str]) -> String {
+  if yaml.trim().is_empty() || changed.contains(&"tags") {
+    return format_frontmatter(frontmatter);
+  }
+
+  let mut lines: Vec<String> = yaml.lines().map(str::to_string).collect();
+  for key in changed {
+    let Some(value) = field_value(frontmatter, key) else {
+      return format_frontmatter(frontmatter);
+    };
+
+    let line = format!("{}: {}", key, value);
+    let prefix = format!("{}:", key);
+    match lines.iter().position(|line| line.starts_with(&prefix)) {
+      Some(index) => {
+        let continued = lines
+          .get(index + 1)
+          .map(|next| next.starts_with([' ', '\t', '-']))
+          .unwrap_or(false);
+        if continued {
+          return format_frontmatter(frontmatter);
+        }
+        lines[index] = line;
+      }
+      None => lines.push(line),
+    }
+  }
+
+  let yaml = lines.join("\n");
+  match serde_yaml::from_str::<Frontmatter>(&yaml) {
+    Ok(parsed) if parsed == *frontmatter => format!("---\n{}\n---\n", yaml),
+    _ => format_frontmatter(frontmatter),
+  }
+}
+
+// The YAML for a single-line field, None for tags and a missing uid.
+fn field_value(frontmatter: &Frontmatter, key: &str) -> Option<String> {
+  match key {
+    "title" => Some(quoted(&frontmatter.title)),
+    "layout" => Some(plain_or_quoted(&frontmatter.layout)),
+    "date" => Some(quoted(&frontmatter.date)),
+    "description" => Some(quoted(&frontmatter.description)),
+    "statut" => Some(plain_or_quoted(&frontmatter.statut)),
+    "updated" => Some(quoted(&frontmatter.updated)),
+    "archived" => Some(frontmatter.archived.to_string()),
+    "uid" => frontmatter.uid.as_deref().map(quoted),
+    _ => None,
+  }
+}
+
+// Plain when the text reads back as the same string on its own, e.g. not
+// `yes`, `12`, `a: b` or `#tag`.
+fn plain_or_quoted(value: &str) -> String {
+  let plain = !value.is_empty()
+    && value.trim() == value
+    && !value.contains(['"', '\'', '\\'])
+    && !value.chars().any(char::is_control)
+    && matches!(
+      serde_yaml::from_str::<serde_yaml::Value>(value),
+      Ok(serde_yaml::Value::String(parsed)) if parsed == value
+    );
+  if plain {
+    value.to_string()
   } else {
-    let tag_lines = frontmatter
-      .tags
-      .iter()
-      .map(|tag| format!("  - {}", escape_yaml_string(tag)))
-      .collect::<Vec<_>>()
-      .join("\n");
-    format!("tags:\n{}\n", tag_lines)
-  };
+    quoted(value)
+  }
+}
 
-  format!(
-    "---\n
-title: \"{}\"\nlayout: {}\ndate: \"{}\"\ndescription: \"{}\"\nstatut: {}\n{}updated: \"{}\"\narchived: {}\n---\n",
-    title,
-    frontmatter.layout,
-    frontmatter.date,
-    description,
-    frontmatter.statut,
-    tags,
-    frontmatter.updated,
-    frontmatter.archived
-  )
+fn quoted(value: &str) -> String {
+  format!("\"{}\"", escape_yaml_string(value))
 }
 
+// Double-quoted YAML escapes: line breaks and other characters YAML does not
+// allow literally (control characters, the BOM, non-characters) are escaped,
+// everything else is kept as is.
 fn escape_yaml_string(value: &str) -> String {
-  value.replace('"', "\\\"")
+  let mut escaped = String::with_capacity(value.len());
+  for ch in value.chars() {
+    match ch {
+      '"' => escaped.push_str("\\\""),
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      '\t' => escaped.push_str("\\t"),
+      '\u{85}' => escaped.push_str("\\N"),
+      '\u{2028}' => escaped.push_str("\\L"),
+      '\u{2029}' => escaped.push_str("\\P"),
+      ch if ch.is_control() || matches!(ch, '\u{feff}' | '\u{fffe}' | '\u{ffff}') => {
+        escaped.push_str(&format!("\\u{:04X}", u32::from(ch)));
+      }
+      ch => escaped.push(ch),
+    }
+  }
+  escaped
 }
 
 fn build_filename(prefix: &str, slug: &str) -> String {
@@ -539,14 +1021,10 @@ fn current_prefix() -> String {
   )
 }
 
-fn parse_date(value: &str) -> Option<NaiveDate> {
-  NaiveDate::parse_from_str(value, "%d-%m-%Y").ok()
-}
-
-fn file_name(path: &Path) -> Result<String, NotesError> {
+fn file_name(path: &Path) -> Result<String, AppError> {
   path
     .file_name()
     .and_then(|name| name.to_str())
     .map(str::to_string)
-    .ok_or_else(|| NotesError::new("Invalid file name"))
+    .ok_or_else(|| AppError::invalid_id("Invalid file name"))
 }
@@ -0,0 +1,133 @@
+use rusqlite::params;
+
+use crate::models::{AppError, NoteSection};
+use crate::services::indexer;
+
+pub struct ParsedSection {
+  pub section: NoteSection,
+  pub content: String,
+}
+
+// Splits a note body on ATX headings (`#` to `######`), ignoring fenced code
+// blocks. Text before the first heading becomes a level 0 section so search
+// can still point at it; it is not part of the outline. Offsets are byte
+// offsets into the body and lines are 1-based body lines.
+pub fn parse_sections(body: &str) -> Vec<ParsedSection> {
+  let mut headings: Vec<(u8, String, usize, usize)> = Vec::new();
+  let mut fence: Option<&str> = None;
+  let mut offset = 0;
+
+  for (index, line) in body.split_inclusive('\n').enumerate() {
+    let trimmed = line.trim_start();
+    if let Some(marker) = fence {
+      if trimmed.starts_with(marker) {
+        fence = None;
+      }
+    } else if trimmed.starts_with("```") {
+      fence = Some("```");
+    } else if trimmed.starts_with("~~~") {
+      fence = Some("~~~");
+    } else if let Some((level, heading)) = parse_heading(line) {
+      headings.push((level, heading, offset, index + 1));
+    }
+    offset += line.len();
+  }
+
+  let mut sections = Vec::new();
+  let first_heading = headings
+    .first()
+    .map(|heading| heading.2)
+    .unwrap_or(body.len());
+  if !body[..first_heading].trim().is_empty() {
+    sections.push(ParsedSection {
+      section: NoteSection {
+        level: 0,
+        heading: String::new(),
+        start: 0,
+        end: first_heading,
+        line: 1,
+      },
+      content: body[..first_heading].to_string(),
+    });
+  }
+
+  for (index, (level, heading, start, line)) in headings.iter().enumerate() {
+    let end = headings[index + 1..]
+      .iter()
+      .find(|next| next.0 <= *level)
+      .map(|next| next.2)
+      .unwrap_or(body.len());
+    // Search content stops at the next heading of any level so a match
+    // points at the most specific section.
+    let content_end = headings
+      .get(index + 1)
+      .map(|next| next.2)
+      .unwrap_or(body.len());
+    sections.push(ParsedSection {
+      section: NoteSection {
+        level: *level,
+        heading: heading.clone(),
+        start: *start,
+        end,
+        line: *line,
+      },
+      content: body[*start..content_end].to_string(),
+    });
+  }
+
+  sections
+}
+
+// Offsets and lines are stored relative to the whole file, as the editor
+// holds it (`NoteDetail.content`), not to the body they were parsed from.
+pub fn get_outline(id: &str) -> Result<Vec<NoteSection>, AppError> {
+  let conn = indexer::open_connection()?;
+  indexer::ensure_schema(&conn)?;
+
+  let mut statement = conn
+    .prepare(
+      "SELECT level, heading, start, end, line FROM sections
+       WHERE note_id = ?1 AND level > 0
+       ORDER BY start",
+    )
+    .map_err(|error| AppError::index("Prepare outline failed", error))?;
+  let rows = statement
+    .query_map(params![id], |row| {
+      Ok(NoteSection {
+        level: row.get(0)?,
+        heading: row.get(1)?,
+        start: row.get::<_, i64>(2)? as usize,
+        end: row.get::<_, i64>(3)? as usize,
+        line: row.get::<_, i64>(4)? as usize,
+      })
+    })
+    .map_err(|error| AppError::index("Outline query failed", error))?;
+
+  let mut sections = Vec::new();
+  for section in rows {
+    sections.push(section.map_err(|error| AppError::index("Row error", error))?);
+  }
+  Ok(sections)
+}
+
+fn parse_heading(line: &str) -> Option<(u8, String)> {
+  let line = line.trim_end_matches(['\n', '\r']);
+  let indent = line.len() - line.trim_start_matches(' ').len();
+  if indent > 3 {
+    return None;
+  }
+
+  let rest = &line[indent..];
+  let level = rest.chars().take_while(|ch| *ch == '#').count();
+  if level == 0 || level > 6 {
+    return None;
+  }
+
+  let text = &rest[level..];
+  if !text.is_empty() && !text.starts_with([' ', '\t']) {
+    return None;
+  }
+
+  let heading = text.trim().trim_end_matches('#').trim_end();
+  Some((level as u8, heading.to_string()))
+}
@@ -0,0 +1,120 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::models::{AppError, NoteMetadata, SavedSearch, SavedSearchCount};
+use crate::services::{notes_fs, search};
+
+const SAVED_SEARCHES_FILE: &str = "saved-searches.json";
+
+pub fn list_saved_searches() -> Result<Vec<SavedSearchCount>, AppError> {
+  let searches = read_saved_searches()?;
+  let mut counted = Vec::new();
+  for saved in searches {
+    let count = search::run_query(&saved.query)?.len();
+    counted.push(SavedSearchCount {
+      search: saved,
+      count,
+    });
+  }
+
+  Ok(counted)
+}
+
+pub fn run_saved_search(id: &str) -> Result<Vec<NoteMetadata>, AppError> {
+  let searches = read_saved_searches()?;
+  let saved = searches
+    .iter()
+    .find(|saved| saved.id == id)
+    .ok_or_else(|| AppError::not_found("Saved search not found"))?;
+
+  search::run_query(&saved.query)
+}
+
+pub fn create_saved_search(name: &str, query: &str) -> Result<SavedSearch, AppError> {
+  validate_saved_search(name, query)?;
+
+  let mut searches = read_saved_searches()?;
+  let base = notes_fs::slugify_title(name.trim()).to_lowercase();
+  let mut id = base.clone();
+  let mut counter = 2;
+  while searches.iter().any(|saved| saved.id == id) {
+    id = format!("{}_{}", base, counter);
+    counter += 1;
+  }
+
+  let saved = SavedSearch {
+    id,
+    name: name.trim().to_string(),
+    query: query.trim().to_string(),
+  };
+  searches.push(saved.clone());
+  write_saved_searches(&searches)?;
+  Ok(saved)
+}
+
+pub fn update_saved_search(id: &str, name: &str, query: &str) -> Result<SavedSearch, AppError> {
+  validate_saved_search(name, query)?;
+
+  let mut searches = read_saved_searches()?;
+  let saved = searches
+    .iter_mut()
+    .find(|saved| saved.id == id)
+    .ok_or_else(|| AppError::not_found("Saved search not found"))?;
+  saved.name = name.trim().to_string();
+  saved.query = query.trim().to_string();
+  let updated = saved.clone();
+
+  write_saved_searches(&searches)?;
+  Ok(updated)
+}
+
+pub fn delete_saved_search(id: &str) -> Result<(), AppError> {
+  let mut searches = read_saved_searches()?;
+  let before = searches.len();
+  searches.retain(|saved| saved.id != id);
+  if searches.len() == before {
+    return Err(AppError::not_found("Saved search not found"));
+  }
+
+  write_saved_searches(&searches)
+}
+
+fn validate_saved_search(name: &str, query: &str) -> Result<(), AppError> {
+  if name.trim().is_empty() {
+    return Err(AppError::invalid_input("Missing saved search name"));
+  }
+
+  if query.trim().is_empty() {
+    return Err(AppError::invalid_input("Missing saved search query"));
+  }
+
+  Ok(())
+}
+
+// Stored inside the notes folder so Syncthing carries them to other devices.
+fn saved_searches_path() -> Result<PathBuf, AppError> {
+  notes_fs::vault_config_dir().map(|dir| dir.join(SAVED_SEARCHES_FILE))
+}
+
+fn read_saved_searches() -> Result<Vec<SavedSearch>, AppError> {
+  let path = saved_searches_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+
+  let raw =
+    fs::read_to_string(&path).map_err(|error| AppError::io("Read saved searches failed", error))?;
+  serde_json::from_str(&raw)
+    .map_err(|error| AppError::invalid_data("Invalid saved searches file", error))
+}
+
+fn write_saved_searches(searches: &[SavedSearch]) -> Result<(), AppError> {
+  let path = saved_searches_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| AppError::io("Create config dir failed", error))?;
+  }
+
+  let raw = serde_json::to_string_pretty(searches)
+    .map_err(|error| AppError::internal("Serialize saved searches failed", error))?;
+  fs::write(&path, raw).map_err(|error| AppError::io("Write saved searches failed", error))
+}
@@ -1,77 +1,296 @@
-use std::fmt;
+use std::collections::HashMap;
 
-use rusqlite::params;
+use chrono::Local;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, Row};
 
-use crate::models::NoteMetadata;
-use crate::services::{indexer, notes_fs};
+use crate::models::{
+  AppError, ListQuery, NoteMetadata, NotePage, NoteSection, SearchHit, SearchRanking,
+  SortDirection, SortKey,
+};
+use crate::services::{dates, indexer};
 
-#[derive(Debug)]
-pub struct SearchError {
-  details: String,
+pub fn search_notes(query: &str, ranking: &SearchRanking) -> Result<Vec<SearchHit>, AppError> {
+  if query.trim().is_empty() {
+    let notes = list_notes(&ListQuery::default())?.notes;
+    return Ok(
+      notes
+        .into_iter()
+        .map(|note| SearchHit {
+          note,
+          section: None,
+        })
+        .collect(),
+    );
+  }
+
+  let conn = indexer::open_connection()?;
+  indexer::ensure_schema(&conn)?;
+
+  // bm25 takes one weight per column, in declaration order (id is unindexed).
+  let mut statement = conn
+    .prepare(
+      "SELECT n.id, n.title, n.layout, n.date, n.description, n.statut, n.tags, n.updated,
+         n.archived, n.uid, n.invalid, bm25(notes_fts, 0.0, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10) AS rank
+       FROM notes_fts
+       JOIN notes n ON n.id = notes_fts.id
+       WHERE notes_fts MATCH ?1
+       ORDER BY rank",
+    )
+    .map_err(|error| AppError::index("Prepare search failed", error))?;
+
+  let rows = statement
+    .query_map(
+      params![
+        query,
+        ranking.title,
+        ranking.layout,
+        ranking.date,
+        ranking.description,
+        ranking.statut,
+        ranking.tags,
+        ranking.updated,
+        ranking.archived,
+        ranking.body
+      ],
+      |row| Ok((note_from_row(row)?, row.get::<_, f64>(11)?)),
+    )
+    .map_err(|error| AppError::index("Search failed", error))?;
+
+  let mut scored = Vec::new();
+  for row in rows {
+    let (note, rank) = row.map_err(|error| AppError::index("Row error", error))?;
+    let score = relevance_score(rank, &note.updated, ranking);
+    scored.push((note, score));
+  }
+
+  scored.sort_by(|left, right| right.1.total_cmp(&left.1));
+  let mut sections = best_sections(&conn, query);
+  Ok(
+    scored
+      .into_iter()
+      .map(|(note, _)| {
+        let section = sections.remove(&note.id);
+        SearchHit { note, section }
+      })
+      .collect(),
+  )
+}
+
+pub fn list_notes(query: &ListQuery) -> Result<NotePage, AppError> {
+  let conn = indexer::open_connection()?;
+  indexer::ensure_schema(&conn)?;
+
+  let mut clauses = Vec::new();
+  let mut values = Vec::new();
+  if let Some(statut) = &query.statut {
+    clauses.push("statut = ?");
+    values.push(Value::Text(statut.clone()));
+  }
+  if let Some(archived) = query.archived {
+    clauses.push("archived = ?");
+    values.push(Value::Integer(i64::from(archived)));
+  }
+  if let Some(invalid) = query.invalid {
+    clauses.push("invalid = ?");
+    values.push(Value::Integer(i64::from(invalid)));
+  }
+  for tag in &query.tags {
+    clauses.push("id IN (SELECT note_id FROM note_tags WHERE tag = ?)");
+    values.push(Value::Text(tag.clone()));
+  }
+
+  let where_clause = if clauses.is_empty() {
+    String::new()
+  } else {
+    format!("WHERE {}", clauses.join(" AND "))
+  };
+
+  let total: i64 = conn
+    .query_row(
+      &format!("SELECT COUNT(*) FROM notes {}", where_clause),
+      params_from_iter(values.iter()),
+      |row| row.get(0),
+    )
+    .map_err(|error| AppError::index("Count notes failed", error))?;
+
+  let direction = match query.direction {
+    SortDirection::Asc => "ASC",
+    SortDirection::Desc => "DESC",
+  };
+  let order = match query.sort {
+    SortKey::Created => format!("created_key {0}, title {0}", direction),
+    SortKey::Updated => format!("updated_key {0}, title {0}", direction),
+    SortKey::Title => format!("title COLLATE NOCASE {}, created_key DESC", direction),
+  };
+
+  let limit = query.limit.map(|limit| limit as i64).unwrap_or(-1);
+  values.push(Value::Integer(limit));
+  values.push(Value::Integer(query.offset as i64));
+
+  let mut statement = conn
+    .prepare(&format!(
+      "SELECT id, title, layout, date, description, statut, tags, updated, archived, uid, invalid
+       FROM notes
+       {}
+       ORDER BY {}
+       LIMIT ? OFFSET ?",
+      where_clause, order
+    ))
+    .map_err(|error| AppError::index("Prepare list failed", error))?;
+
+  let rows = statement
+    .query_map(params_from_iter(values.iter()), note_from_row)
+    .map_err(|error| AppError::index("List failed", error))?;
+
+  let mut notes = Vec::new();
+  for note in rows {
+    notes.push(note.map_err(|error| AppError::index("Row error", error))?);
+  }
+
+  Ok(NotePage {
+    notes,
+    total: total as usize,
+  })
 }
 
-impl SearchError {
-  pub fn new(details: impl Into<String>) -> Self {
-    Self {
-      details: details.into(),
+// Runs a query mixing filters (`statut:chantier`, `tag:client-x`,
+// `archived:true`) with free text matched through the full-text index.
+pub fn run_query(query: &str) -> Result<Vec<NoteMetadata>, AppError> {
+  let mut filters = ListQuery::default();
+  let mut terms = Vec::new();
+
+  for token in query.split_whitespace() {
+    match token.split_once(':') {
+      Some(("statut", value)) if !value.is_empty() => filters.statut = Some(value.to_string()),
+      Some(("tag" | "tags", value)) if !value.is_empty() => filters.tags.push(value.to_string()),
+      Some(("archived", "true")) => filters.archived = Some(true),
+      Some(("archived", "false")) => filters.archived = Some(false),
+      _ => terms.push(fts_term(token)),
     }
   }
+
+  if terms.is_empty() {
+    return list_notes(&filters).map(|page| page.notes);
+  }
+
+  let hits = search_notes(&terms.join(" "), &SearchRanking::default())?;
+  Ok(hits
+    .into_iter()
+    .map(|hit| hit.note)
+    .filter(|note| matches_filters(note, &filters))
+    .collect())
 }
 
-impl fmt::Display for SearchError {
-  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(formatter, "{}", self.details)
+// Finds the best matching section of each note for the same query. Queries
+// using note-level column filters (`title:...`) have no meaning against
+// sections, so a failing match just leaves the hits without a section.
+fn best_sections(conn: &Connection, query: &str) -> HashMap<String, NoteSection> {
+  let mut best = HashMap::new();
+  let Ok(mut statement) = conn.prepare(
+    "SELECT s.note_id, s.level, s.heading, s.start, s.end, s.line
+     FROM sections_fts
+     JOIN sections s ON s.note_id = sections_fts.note_id AND s.position = sections_fts.position
+     WHERE sections_fts MATCH ?1
+     ORDER BY bm25(sections_fts, 0.0, 0.0, 5.0, 1.0)",
+  ) else {
+    return best;
+  };
+
+  let Ok(rows) = statement.query_map(params![query], |row| {
+    Ok((
+      row.get::<_, String>(0)?,
+      NoteSection {
+        level: row.get(1)?,
+        heading: row.get(2)?,
+        start: row.get::<_, i64>(3)? as usize,
+        end: row.get::<_, i64>(4)? as usize,
+        line: row.get::<_, i64>(5)? as usize,
+      },
+    ))
+  }) else {
+    return best;
+  };
+
+  for (note_id, section) in rows.flatten() {
+    best.entry(note_id).or_insert(section);
   }
+  best
 }
 
-impl std::error::Error for SearchError {}
+// A free-text term as an FTS5 string, so `client-x` or a stray `"` is
+// searched for instead of read as query syntax. A trailing `*` stays a
+// prefix search.
+fn fts_term(token: &str) -> String {
+  let (word, prefix) = match token.strip_suffix('*') {
+    Some(word) if !word.is_empty() => (word, "*"),
+    _ => (token, ""),
+  };
+  format!("\"{}\"{}", word.replace('"', "\"\""), prefix)
+}
 
-pub fn search_notes(query: &str) -> Result<Vec<NoteMetadata>, SearchError> {
-  if query.trim().is_empty() {
-    return notes_fs::list_notes().map_err(|error| SearchError::new(error.to_string()));
+fn matches_filters(note: &NoteMetadata, filters: &ListQuery) -> bool {
+  if let Some(statut) = &filters.statut {
+    if &note.statut != statut {
+      return false;
+    }
   }
 
-  let conn = indexer::open_connection().map_err(|error| SearchError::new(error.to_string()))?;
-  indexer::ensure_schema(&conn).map_err(|error| SearchError::new(error.to_string()))?;
+  if let Some(archived) = filters.archived {
+    if note.archived != archived {
+      return false;
+    }
+  }
 
-  let mut statement = conn
-    .prepare(
-      "SELECT id, title, layout, date, description, statut, tags, updated, archived
-       FROM notes_fts
-       WHERE notes_fts MATCH ?
-       ORDER BY bm25(notes_fts)",
-    )
-    .map_err(|error| SearchError::new(format!("Prepare search failed: {}", error)))?;
-
-  let notes = statement
-    .query_map(params![query], |row| {
-      let tags_raw: String = row.get(6)?;
-      let tags = tags_raw
-        .split(',')
-        .map(|tag| tag.trim())
-        .filter(|tag| !tag.is_empty())
-        .map(str::to_string)
-        .collect::<Vec<_>>();
-      let archived_raw: String = row.get(8)?;
-
-      Ok(NoteMetadata {
-        id: row.get(0)?,
-        title: row.get(1)?,
-        layout: row.get(2)?,
-        date: row.get(3)?,
-        description: row.get(4)?,
-        statut: row.get(5)?,
-        tags,
-        updated: row.get(7)?,
-        archived: archived_raw == "true",
-      })
-    })
-    .map_err(|error| SearchError::new(format!("Search failed: {}", error)))?;
+  filters.tags.iter().all(|wanted| {
+    note
+      .tags
+      .iter()
+      .any(|tag| tag.to_lowercase() == wanted.to_lowercase())
+  })
+}
+
+// Expects the notes table columns in declaration order from id to uid, then
+// invalid.
+fn note_from_row(row: &Row<'_>) -> rusqlite::Result<NoteMetadata> {
+  let tags_raw: String = row.get(6)?;
+  Ok(NoteMetadata {
+    id: row.get(0)?,
+    title: row.get(1)?,
+    layout: row.get(2)?,
+    date: row.get(3)?,
+    description: row.get(4)?,
+    statut: row.get(5)?,
+    tags: split_tags(&tags_raw),
+    updated: row.get(7)?,
+    archived: row.get(8)?,
+    uid: row.get(9)?,
+    invalid: row.get(10)?,
+  })
+}
 
-  let mut results = Vec::new();
-  for note in notes {
-    results.push(note.map_err(|error| SearchError::new(format!("Row error: {}", error)))?);
+pub(crate) fn split_tags(raw: &str) -> Vec<String> {
+  raw
+    .split(',')
+    .map(|tag| tag.trim())
+    .filter(|tag| !tag.is_empty())
+    .map(str::to_string)
+    .collect()
+}
+
+// bm25 returns lower-is-better values; flip the sign and scale by how recently
+// the note was updated so fresh notes win ties between similar matches.
+fn relevance_score(rank: f64, updated: &str, ranking: &SearchRanking) -> f64 {
+  let relevance = -rank;
+  if ranking.recency_boost <= 0.0 || ranking.recency_half_life_days <= 0.0 {
+    return relevance;
   }
 
-  Ok(results)
+  let Some(updated) = dates::parse_updated(updated) else {
+    return relevance;
+  };
+
+  let age_days = (Local::now().fixed_offset() - updated).num_minutes().max(0) as f64 / 1440.0;
+  let recency = 0.5_f64.powf(age_days / ranking.recency_half_life_days);
+  relevance * (1.0 + ranking.recency_boost * recency)
 }
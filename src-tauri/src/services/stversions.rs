@@ -0,0 +1,100 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+
+use crate::models::{AppError, NoteDetail, NoteVersion};
+use crate::services::{dates, history, notes_fs};
+
+const VERSIONS_DIR: &str = ".stversions";
+const VERSION_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+// Syncthing's simple and staggered versioning keep `<stem>~YYYYMMDD-HHMMSS.md`
+// copies in `.stversions`, mirroring the folder layout; notes live at the
+// root of the folder so their versions do too. Sorted newest first.
+pub fn list_versions(id: &str) -> Result<Vec<NoteVersion>, AppError> {
+  let stem = note_stem(id)?;
+  let dir = versions_dir()?;
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let entries =
+    fs::read_dir(&dir).map_err(|error| AppError::io("Read .stversions failed", error))?;
+  let prefix = format!("{}~", stem);
+  let mut versions = Vec::new();
+  for entry in entries {
+    let entry = entry.map_err(|error| AppError::io("Read entry failed", error))?;
+    let name = entry.file_name().to_string_lossy().to_string();
+    let Some(stamp) = name
+      .strip_prefix(&prefix)
+      .and_then(|rest| rest.strip_suffix(".md"))
+    else {
+      continue;
+    };
+    if let Ok(created) = NaiveDateTime::parse_from_str(stamp, VERSION_FORMAT) {
+      versions.push((created, stamp.to_string()));
+    }
+  }
+
+  versions.sort_by_key(|version| Reverse(version.0));
+  Ok(
+    versions
+      .into_iter()
+      .map(|(created, stamp)| NoteVersion {
+        id: stamp,
+        created: dates::format_timestamp(created),
+      })
+      .collect(),
+  )
+}
+
+pub fn get_version(id: &str, version: &str) -> Result<String, AppError> {
+  let path = version_path(id, version)?;
+  if !path.is_file() {
+    return Err(AppError::not_found("Version not found"));
+  }
+
+  fs::read_to_string(&path).map_err(|error| AppError::io("Read version failed", error))
+}
+
+pub fn diff_version(id: &str, version: &str) -> Result<String, AppError> {
+  let old = get_version(id, version)?;
+  let current = notes_fs::get_note(id)?;
+  Ok(history::unified_diff(
+    &old,
+    &current.content,
+    version,
+    "current",
+  ))
+}
+
+// Restoring writes the version over the note (keeping Syncthing's copy) after
+// the current text has been saved to the local history.
+pub fn restore_version(id: &str, version: &str) -> Result<NoteDetail, AppError> {
+  let content = get_version(id, version)?;
+  history::snapshot_note(id, true)?;
+  notes_fs::save_note(id, &content)
+}
+
+fn version_path(id: &str, version: &str) -> Result<PathBuf, AppError> {
+  if NaiveDateTime::parse_from_str(version, VERSION_FORMAT).is_err() {
+    return Err(AppError::invalid_id("Invalid version"));
+  }
+
+  Ok(versions_dir()?.join(format!("{}~{}.md", note_stem(id)?, version)))
+}
+
+fn note_stem(id: &str) -> Result<&str, AppError> {
+  if id.contains(['/', '\\']) || id.contains("..") {
+    return Err(AppError::invalid_id("Invalid note ID"));
+  }
+
+  id.strip_suffix(".md")
+    .ok_or_else(|| AppError::invalid_id("Invalid note ID: must be a markdown file"))
+}
+
+fn versions_dir() -> Result<PathBuf, AppError> {
+  notes_fs::notes_dir().map(|dir| dir.join(VERSIONS_DIR))
+}
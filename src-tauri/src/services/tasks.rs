@@ -0,0 +1,212 @@
+use regex::Regex;
+use rusqlite::params_from_iter;
+use rusqlite::types::Value;
+
+use crate::models::{AppError, NoteDetail, NoteTask, TaskQuery};
+use crate::services::{indexer, notes_fs, search};
+
+const TASK_PATTERN: &str = r"^\s*(?:[-*+]|\d+[.)])\s+\[([ xX])\]\s+(.*?)\s*$";
+const DUE_PATTERN: &str = r"@due\((\d{4}-\d{2}-\d{2})\)|📅\s*(\d{4}-\d{2}-\d{2})";
+const TAG_PATTERN: &str = r"(?:^|\s)#([\p{L}\p{N}_/-]+)";
+
+pub struct ParsedTask {
+  pub line: usize,
+  pub text: String,
+  pub done: bool,
+  pub due: Option<String>,
+  pub tags: Vec<String>,
+}
+
+// Finds `- [ ]` / `- [x]` checkboxes (also `*`, `+` and numbered items)
+// outside fenced code blocks. Lines are 1-based body lines.
+pub fn parse_tasks(body: &str) -> Vec<ParsedTask> {
+  let (Ok(task_regex), Ok(due_regex), Ok(tag_regex)) = (
+    Regex::new(TASK_PATTERN),
+    Regex::new(DUE_PATTERN),
+    Regex::new(TAG_PATTERN),
+  ) else {
+    return Vec::new();
+  };
+
+  let mut tasks = Vec::new();
+  let mut fence: Option<&str> = None;
+  for (index, line) in body.lines().enumerate() {
+    let trimmed = line.trim_start();
+    if let Some(marker) = fence {
+      if trimmed.starts_with(marker) {
+        fence = None;
+      }
+      continue;
+    }
+    if trimmed.starts_with("```") {
+      fence = Some("```");
+      continue;
+    }
+    if trimmed.starts_with("~~~") {
+      fence = Some("~~~");
+      continue;
+    }
+
+    let Some(captures) = task_regex.captures(line) else {
+      continue;
+    };
+    let text = captures[2].to_string();
+    let due = due_regex.captures(&text).and_then(|due| {
+      due
+        .get(1)
+        .or_else(|| due.get(2))
+        .map(|date| date.as_str().to_string())
+    });
+    let tags = tag_regex
+      .captures_iter(&text)
+      .map(|tag| tag[1].to_string())
+      .collect();
+
+    tasks.push(ParsedTask {
+      line: index + 1,
+      done: &captures[1] != " ",
+      text,
+      due,
+      tags,
+    });
+  }
+
+  tasks
+}
+
+pub fn list_tasks(query: &TaskQuery) -> Result<Vec<NoteTask>, AppError> {
+  let conn = indexer::open_connection()?;
+  indexer::ensure_schema(&conn)?;
+
+  let mut clauses = Vec::new();
+  let mut values = Vec::new();
+  if !query.include_done {
+    clauses.push("t.done = 0");
+  }
+  if !query.include_archived {
+    clauses.push("n.archived = 0");
+  }
+  if let Some(note_id) = &query.note_id {
+    clauses.push("t.note_id = ?");
+    values.push(Value::Text(note_id.clone()));
+  }
+  if let Some(before) = &query.due_before {
+    clauses.push("t.due IS NOT NULL AND t.due <= ?");
+    values.push(Value::Text(before.clone()));
+  }
+  if let Some(after) = &query.due_after {
+    clauses.push("t.due IS NOT NULL AND t.due >= ?");
+    values.push(Value::Text(after.clone()));
+  }
+
+  let where_clause = if clauses.is_empty() {
+    String::new()
+  } else {
+    format!("WHERE {}", clauses.join(" AND "))
+  };
+
+  // Tasks with a due date come first, soonest first.
+  let mut statement = conn
+    .prepare(&format!(
+      "SELECT t.note_id, n.title, t.line, t.text, t.done, t.due, t.tags, n.tags
+       FROM tasks t
+       JOIN notes n ON n.id = t.note_id
+       {}
+       ORDER BY t.due IS NULL, t.due, n.title COLLATE NOCASE, t.line",
+      where_clause
+    ))
+    .map_err(|error| AppError::index("Prepare tasks failed", error))?;
+
+  let rows = statement
+    .query_map(params_from_iter(values.iter()), |row| {
+      let tags: String = row.get(6)?;
+      let note_tags: String = row.get(7)?;
+      Ok((
+        NoteTask {
+          note_id: row.get(0)?,
+          note_title: row.get(1)?,
+          line: row.get::<_, i64>(2)? as usize,
+          text: row.get(3)?,
+          done: row.get(4)?,
+          due: row.get(5)?,
+          tags: search::split_tags(&tags),
+        },
+        search::split_tags(&note_tags),
+      ))
+    })
+    .map_err(|error| AppError::index("List tasks failed", error))?;
+
+  let mut tasks = Vec::new();
+  for row in rows {
+    let (task, note_tags) = row.map_err(|error| AppError::index("Row error", error))?;
+    // A tag filter matches tags written on the task or on its note.
+    if let Some(tag) = &query.tag {
+      let wanted = tag.trim_start_matches('#').to_lowercase();
+      if !task
+        .tags
+        .iter()
+        .chain(note_tags.iter())
+        .any(|candidate| candidate.to_lowercase() == wanted)
+      {
+        continue;
+      }
+    }
+    tasks.push(task);
+  }
+
+  Ok(tasks)
+}
+
+// Flips the checkbox on one body line and saves the note through the regular
+// save path, so the frontmatter is validated and `updated` is bumped. The
+// line must still hold the task with `text` as listed: if the file changed
+// since it was indexed the toggle is refused rather than editing the wrong
+// line.
+pub fn toggle_task(note_id: &str, line: usize, text: &str) -> Result<NoteDetail, AppError> {
+  let note = notes_fs::get_note(note_id)?;
+  let raw = note.content;
+
+  let body_start =
+    body_start_line(&raw).ok_or_else(|| AppError::invalid_input("Note has no frontmatter"))?;
+  let body = raw.lines().skip(body_start).collect::<Vec<_>>().join("\n");
+  if !parse_tasks(&body)
+    .iter()
+    .any(|task| task.line == line && task.text == text)
+  {
+    return Err(AppError::stale_revision("No such task on that line"));
+  }
+
+  let task_regex = Regex::new(TASK_PATTERN)
+    .map_err(|error| AppError::internal("Regex compilation failed", error))?;
+  let target = body_start + line - 1;
+  let mut updated = String::with_capacity(raw.len());
+  for (index, raw_line) in raw.split_inclusive('\n').enumerate() {
+    if index != target {
+      updated.push_str(raw_line);
+      continue;
+    }
+
+    let checkbox = task_regex
+      .captures(raw_line.trim_end_matches(['\n', '\r']))
+      .and_then(|captures| captures.get(1))
+      .ok_or_else(|| AppError::stale_revision("No task on that line"))?;
+    let mark = if checkbox.as_str() == " " { "x" } else { " " };
+    updated.push_str(&raw_line[..checkbox.start()]);
+    updated.push_str(mark);
+    updated.push_str(&raw_line[checkbox.end()..]);
+  }
+
+  notes_fs::save_note(note_id, &updated)
+}
+
+// Index of the first line after the closing `---` of the frontmatter.
+fn body_start_line(raw: &str) -> Option<usize> {
+  let mut lines = raw.lines();
+  if lines.next()?.trim_start_matches('\u{feff}').trim() != "---" {
+    return None;
+  }
+
+  lines
+    .position(|line| line.trim() == "---")
+    .map(|index| index + 2)
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Local;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+
+use crate::models::{AppError, ErrorCode, NoteTemplate, TemplateNote};
+use crate::services::dates;
+use crate::services::notes_fs::{self, NewNote};
+
+const TEMPLATES_DIR: &str = "templates";
+const PLACEHOLDER_PATTERN: &str = r"\{\{\s*([\p{L}\p{N}_-]+)\s*\}\}";
+const BUILTIN_PLACEHOLDERS: [&str; 4] = ["date", "time", "title", "cursor"];
+const CURSOR_MARKER: &str = "\u{0}cursor\u{0}";
+
+// Optional frontmatter of a template file. `title` is a title pattern used
+// when the note is created without a title; the other fields become the
+// new note's defaults.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct TemplateFrontmatter {
+  name: Option<String>,
+  title: Option<String>,
+  description: String,
+  statut: Option<String>,
+  tags: Vec<String>,
+}
+
+struct ParsedTemplate {
+  frontmatter: TemplateFrontmatter,
+  body: String,
+}
+
+pub fn list_templates() -> Result<Vec<NoteTemplate>, AppError> {
+  let dir = templates_dir()?;
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let entries =
+    fs::read_dir(&dir).map_err(|error| AppError::io("Cannot read templates dir", error))?;
+  let mut templates = Vec::new();
+  for entry in entries {
+    let entry = entry.map_err(|error| AppError::io("Read entry failed", error))?;
+    let path = entry.path();
+    if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+      continue;
+    }
+    let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+      continue;
+    };
+
+    let parsed = read_template(id)?;
+    templates.push(NoteTemplate {
+      id: id.to_string(),
+      name: parsed
+        .frontmatter
+        .name
+        .clone()
+        .unwrap_or_else(|| id.to_string()),
+      prompts: prompts(&parsed),
+      description: parsed.frontmatter.description,
+      statut: parsed.frontmatter.statut,
+      tags: parsed.frontmatter.tags,
+    });
+  }
+
+  templates.sort_by_key(|template| template.name.to_lowercase());
+  Ok(templates)
+}
+
+// `values` fills the template's custom placeholders (its prompts); missing
+// values are left empty.
+pub fn create_note_from_template(
+  template_id: &str,
+  title: &str,
+  values: &HashMap<String, String>,
+) -> Result<TemplateNote, AppError> {
+  let parsed = read_template(template_id)?;
+  let now = Local::now().naive_local();
+  let date = dates::format_date(now.date(), dates::vault_format());
+  let time = now.format("%H:%M").to_string();
+
+  let title = match (title.trim(), &parsed.frontmatter.title) {
+    ("", Some(pattern)) => render(pattern, &date, &time, "", values)?.replace(CURSOR_MARKER, ""),
+    ("", None) => return Err(AppError::invalid_input("Missing title")),
+    (title, _) => title.to_string(),
+  };
+
+  let body = render(&parsed.body, &date, &time, &title, values)?;
+  let description = render(
+    &parsed.frontmatter.description,
+    &date,
+    &time,
+    &title,
+    values,
+  )?;
+  let cursor = body.find(CURSOR_MARKER);
+  let mut note = NewNote::new(&title, now);
+  note.body = body.replace(CURSOR_MARKER, "");
+  note.description = description.replace(CURSOR_MARKER, "");
+  note.statut = parsed.frontmatter.statut;
+  note.tags = parsed.frontmatter.tags;
+
+  let detail = notes_fs::create_note_with(&note)?;
+  // The body is the tail of the written content.
+  let cursor = cursor.map(|offset| {
+    let start = detail.content.len() - note.body.len() + offset;
+    detail.content[..start].encode_utf16().count()
+  });
+
+  Ok(TemplateNote { detail, cursor })
+}
+
+fn render(
+  text: &str,
+  date: &str,
+  time: &str,
+  title: &str,
+  values: &HashMap<String, String>,
+) -> Result<String, AppError> {
+  let placeholder = Regex::new(PLACEHOLDER_PATTERN)
+    .map_err(|error| AppError::internal("Regex compilation failed", error))?;
+  let rendered = placeholder.replace_all(text, |captures: &Captures<'_>| match &captures[1] {
+    "date" => date.to_string(),
+    "time" => time.to_string(),
+    "title" => title.to_string(),
+    "cursor" => CURSOR_MARKER.to_string(),
+    name => values.get(name).cloned().unwrap_or_default(),
+  });
+  Ok(rendered.into_owned())
+}
+
+// Custom placeholders in order of first appearance, title pattern first.
+fn prompts(parsed: &ParsedTemplate) -> Vec<String> {
+  let Ok(placeholder) = Regex::new(PLACEHOLDER_PATTERN) else {
+    return Vec::new();
+  };
+
+  let sources = [
+    parsed.frontmatter.title.as_deref().unwrap_or_default(),
+    parsed.frontmatter.description.as_str(),
+    parsed.body.as_str(),
+  ];
+  let mut prompts: Vec<String> = Vec::new();
+  for source in sources {
+    for captures in placeholder.captures_iter(source) {
+      let name = &captures[1];
+      if !BUILTIN_PLACEHOLDERS.contains(&name) && !prompts.iter().any(|prompt| prompt == name) {
+        prompts.push(name.to_string());
+      }
+    }
+  }
+  prompts
+}
+
+fn read_template(id: &str) -> Result<ParsedTemplate, AppError> {
+  if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+    return Err(AppError::invalid_id("Invalid template id"));
+  }
+
+  let path = templates_dir()?.join(format!("{}.md", id));
+  if !path.exists() {
+    return Err(AppError::not_found("Template not found"));
+  }
+
+  let raw =
+    fs::read_to_string(&path).map_err(|error| AppError::io("Read template failed", error))?;
+  parse_template(&raw)
+}
+
+// Templates may start with a frontmatter block like notes do; without one,
+// the whole file is the body.
+fn parse_template(raw: &str) -> Result<ParsedTemplate, AppError> {
+  let text = raw.trim_start_matches('\u{feff}');
+  let (yaml, body) = match notes_fs::split_frontmatter(raw) {
+    Ok(split) => split,
+    Err(_) if text.lines().next().map(str::trim) == Some("---") => {
+      return Err(AppError::invalid_input("Template frontmatter not closed"));
+    }
+    Err(_) => {
+      return Ok(ParsedTemplate {
+        frontmatter: TemplateFrontmatter::default(),
+        body: text.to_string(),
+      })
+    }
+  };
+
+  let frontmatter = if yaml.trim().is_empty() {
+    TemplateFrontmatter::default()
+  } else {
+    serde_yaml::from_str(&yaml).map_err(|error| {
+      AppError::with_details(
+        ErrorCode::InvalidFrontmatter,
+        "Invalid template frontmatter",
+        error,
+      )
+    })?
+  };
+
+  Ok(ParsedTemplate { frontmatter, body })
+}
+
+fn templates_dir() -> Result<PathBuf, AppError> {
+  notes_fs::vault_config_dir().map(|dir| dir.join(TEMPLATES_DIR))
+}
@@ -0,0 +1,329 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate};
+use rusqlite::params_from_iter;
+use rusqlite::types::Value;
+
+use crate::models::{AppError, TodoItem, TodoQuery};
+use crate::services::{indexer, notes_fs};
+
+pub const TODO_FILE: &str = "todo.txt";
+pub const DONE_FILE: &str = "done.txt";
+
+// Markor keeps its todo.txt and done.txt next to the notes; these are the
+// only non-Markdown files Velocitext reads.
+pub fn todo_file_name(path: &Path) -> Option<String> {
+  if notes_fs::is_ignored_path(path) {
+    return None;
+  }
+
+  let name = path.file_name()?.to_str()?;
+  if name == TODO_FILE || name == DONE_FILE {
+    Some(name.to_string())
+  } else {
+    None
+  }
+}
+
+// Parses one line of the todo.txt format:
+// `x 2026-10-18 2026-10-01 (A) text +project @context due:2026-10-20`.
+// Blank lines are not items but still count as lines.
+pub fn parse_todo_line(file: &str, line: usize, raw: &str) -> Option<TodoItem> {
+  let raw = raw.trim_end_matches(['\n', '\r']);
+  if raw.trim().is_empty() {
+    return None;
+  }
+
+  let mut rest = raw;
+  let mut priority = None;
+  let mut completion_date = None;
+  let done = rest.starts_with("x ");
+  if done {
+    rest = &rest[2..];
+    let (date, after) = take_date(rest);
+    completion_date = date;
+    rest = after;
+  } else if let Some(letter) = take_priority(rest) {
+    priority = Some(letter.to_string());
+    rest = &rest[4..];
+  }
+
+  let (creation_date, text) = if completion_date.is_some() || !done {
+    take_date(rest)
+  } else {
+    (None, rest)
+  };
+
+  let mut projects = Vec::new();
+  let mut contexts = Vec::new();
+  let mut due = None;
+  for word in text.split_whitespace() {
+    if let Some(project) = word.strip_prefix('+').filter(|value| !value.is_empty()) {
+      projects.push(project.to_string());
+    } else if let Some(context) = word.strip_prefix('@').filter(|value| !value.is_empty()) {
+      contexts.push(context.to_string());
+    } else if let Some(date) = word.strip_prefix("due:") {
+      due = Some(date.to_string());
+    }
+  }
+
+  Some(TodoItem {
+    file: file.to_string(),
+    line,
+    raw: raw.to_string(),
+    done,
+    priority,
+    completion_date,
+    creation_date,
+    text: text.to_string(),
+    projects,
+    contexts,
+    due,
+  })
+}
+
+pub fn read_todo_file(file: &str) -> Result<Vec<TodoItem>, AppError> {
+  let content = read_file(&todo_path(file)?)?;
+  Ok(
+    content
+      .split_inclusive('\n')
+      .enumerate()
+      .filter_map(|(index, raw)| parse_todo_line(file, index + 1, raw))
+      .collect(),
+  )
+}
+
+pub fn list_todo_items(query: &TodoQuery) -> Result<Vec<TodoItem>, AppError> {
+  let conn = indexer::open_connection()?;
+  indexer::ensure_schema(&conn)?;
+
+  let mut clauses = Vec::new();
+  let mut values = Vec::new();
+  if !query.include_done {
+    clauses.push("done = 0");
+  }
+  if let Some(priority) = &query.priority {
+    clauses.push("priority = ?");
+    values.push(Value::Text(priority.to_uppercase()));
+  }
+
+  let where_clause = if clauses.is_empty() {
+    String::new()
+  } else {
+    format!("WHERE {}", clauses.join(" AND "))
+  };
+
+  // Open items first, by priority (items without one last), then file order.
+  let mut statement = conn
+    .prepare(&format!(
+      "SELECT file, line, raw FROM todo_items
+       {}
+       ORDER BY done, priority IS NULL, priority, file DESC, line",
+      where_clause
+    ))
+    .map_err(|error| AppError::index("Prepare todo list failed", error))?;
+  let rows = statement
+    .query_map(params_from_iter(values.iter()), |row| {
+      Ok((
+        row.get::<_, String>(0)?,
+        row.get::<_, i64>(1)? as usize,
+        row.get::<_, String>(2)?,
+      ))
+    })
+    .map_err(|error| AppError::index("Todo list failed", error))?;
+
+  let mut items = Vec::new();
+  for row in rows {
+    let (file, line, raw) = row.map_err(|error| AppError::index("Row error", error))?;
+    let Some(item) = parse_todo_line(&file, line, &raw) else {
+      continue;
+    };
+    if let Some(project) = &query.project {
+      if !item.projects.iter().any(|candidate| candidate == project) {
+        continue;
+      }
+    }
+    if let Some(context) = &query.context {
+      if !item.contexts.iter().any(|candidate| candidate == context) {
+        continue;
+      }
+    }
+    items.push(item);
+  }
+
+  Ok(items)
+}
+
+// Appends an item to todo.txt, adding today's creation date like Markor does.
+pub fn add_todo_item(text: &str) -> Result<TodoItem, AppError> {
+  let text = text.trim();
+  if text.is_empty() {
+    return Err(AppError::invalid_input("Missing todo text"));
+  }
+  if text.contains(['\n', '\r']) {
+    return Err(AppError::invalid_input("A todo item must fit on one line"));
+  }
+
+  let parsed = parse_todo_line(TODO_FILE, 0, text)
+    .ok_or_else(|| AppError::invalid_input("Missing todo text"))?;
+  let line = if parsed.done || parsed.creation_date.is_some() {
+    text.to_string()
+  } else {
+    let today = Local::now().format("%Y-%m-%d");
+    match &parsed.priority {
+      Some(priority) => format!("({}) {} {}", priority, today, &text[4..]),
+      None => format!("{} {}", today, text),
+    }
+  };
+
+  let path = todo_path(TODO_FILE)?;
+  let mut content = read_file(&path)?;
+  let ending = line_ending(&content);
+  if !content.is_empty() && !content.ends_with('\n') {
+    content.push_str(ending);
+  }
+  content.push_str(&line);
+  content.push_str(ending);
+  write_file(&path, &content)?;
+
+  let number = content.split_inclusive('\n').count();
+  parse_todo_line(TODO_FILE, number, &line)
+    .ok_or_else(|| AppError::invalid_input("Missing todo text"))
+}
+
+// Marks a todo.txt line as done. Only that line changes: the priority is kept
+// as a `pri:` tag, as the todo.txt format drops it from completed items.
+// `raw` is the item as listed; if another app changed the file since, the
+// line no longer holds it and the completion is refused.
+pub fn complete_todo_item(line: usize, raw: &str) -> Result<TodoItem, AppError> {
+  let path = todo_path(TODO_FILE)?;
+  let content = read_file(&path)?;
+  let mut updated = String::with_capacity(content.len() + 16);
+  let mut completed = None;
+
+  for (index, current) in content.split_inclusive('\n').enumerate() {
+    if index + 1 != line {
+      updated.push_str(current);
+      continue;
+    }
+
+    let item = parse_todo_line(TODO_FILE, line, current)
+      .filter(|item| !item.done && item.raw == raw)
+      .ok_or_else(|| AppError::stale_revision("No open todo item on that line"))?;
+    let mut done_line = format!("x {}", Local::now().format("%Y-%m-%d"));
+    if let Some(created) = &item.creation_date {
+      done_line.push_str(&format!(" {}", created));
+    }
+    done_line.push_str(&format!(" {}", item.text));
+    if let Some(priority) = &item.priority {
+      done_line.push_str(&format!(" pri:{}", priority));
+    }
+
+    updated.push_str(&done_line);
+    updated.push_str(&current[item.raw.len()..]);
+    completed = parse_todo_line(TODO_FILE, line, &done_line);
+  }
+
+  let completed =
+    completed.ok_or_else(|| AppError::stale_revision("No open todo item on that line"))?;
+  write_file(&path, &updated)?;
+  Ok(completed)
+}
+
+// Moves completed items from todo.txt to the end of done.txt and returns how
+// many were moved. Other lines are written back untouched.
+pub fn archive_done_items() -> Result<usize, AppError> {
+  let open_path = todo_path(TODO_FILE)?;
+  let done_path = todo_path(DONE_FILE)?;
+  let content = read_file(&open_path)?;
+
+  let mut kept = String::with_capacity(content.len());
+  let mut moved = Vec::new();
+  for (index, raw) in content.split_inclusive('\n').enumerate() {
+    match parse_todo_line(TODO_FILE, index + 1, raw) {
+      Some(item) if item.done => moved.push(item.raw),
+      _ => kept.push_str(raw),
+    }
+  }
+
+  if moved.is_empty() {
+    return Ok(0);
+  }
+
+  let mut done = read_file(&done_path)?;
+  let ending = if done.is_empty() {
+    line_ending(&content)
+  } else {
+    line_ending(&done)
+  };
+  if !done.is_empty() && !done.ends_with('\n') {
+    done.push_str(ending);
+  }
+  for raw in &moved {
+    done.push_str(raw);
+    done.push_str(ending);
+  }
+
+  // done.txt is written first: if the second write fails the items are
+  // duplicated rather than lost.
+  write_file(&done_path, &done)?;
+  write_file(&open_path, &kept)?;
+  Ok(moved.len())
+}
+
+fn take_priority(value: &str) -> Option<char> {
+  let bytes = value.as_bytes();
+  if bytes.len() >= 4
+    && bytes[0] == b'('
+    && bytes[1].is_ascii_uppercase()
+    && bytes[2] == b')'
+    && bytes[3] == b' '
+  {
+    Some(bytes[1] as char)
+  } else {
+    None
+  }
+}
+
+fn take_date(value: &str) -> (Option<String>, &str) {
+  let Some(candidate) = value.get(..10) else {
+    return (None, value);
+  };
+  let rest = &value[10..];
+  if NaiveDate::parse_from_str(candidate, "%Y-%m-%d").is_err()
+    || !(rest.is_empty() || rest.starts_with(' '))
+  {
+    return (None, value);
+  }
+
+  (Some(candidate.to_string()), rest.trim_start_matches(' '))
+}
+
+fn line_ending(content: &str) -> &'static str {
+  if content.contains("\r\n") {
+    "\r\n"
+  } else {
+    "\n"
+  }
+}
+
+fn todo_path(file: &str) -> Result<PathBuf, AppError> {
+  notes_fs::notes_dir().map(|dir| dir.join(file))
+}
+
+fn read_file(path: &Path) -> Result<String, AppError> {
+  if !path.exists() {
+    return Ok(String::new());
+  }
+
+  fs::read_to_string(path).map_err(|error| AppError::io("Read todo file failed", error))
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), AppError> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| AppError::io("Create notes dir failed", error))?;
+  }
+
+  fs::write(path, content).map_err(|error| AppError::io("Write todo file failed", error))
+}
@@ -0,0 +1,198 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local};
+
+use crate::models::{AppError, NoteDetail, TrashSettings, TrashedNote};
+use crate::services::{dates, indexer, notes_fs};
+
+// The trash lives in the local data dir rather than the notes folder so sync
+// tools never see it: deleting a note removes it from the other devices, and
+// the copy here is only recoverable on this one.
+const TRASH_DIR: &str = "trash";
+const MANIFEST_FILE: &str = "trash.json";
+const TRASH_SETTINGS_FILE: &str = "trash.json";
+const ENTRY_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+pub fn get_trash_settings() -> Result<TrashSettings, AppError> {
+  let path = settings_path()?;
+  if !path.exists() {
+    return Ok(TrashSettings::default());
+  }
+
+  let raw =
+    fs::read_to_string(&path).map_err(|error| AppError::io("Read trash settings failed", error))?;
+  serde_json::from_str(&raw)
+    .map_err(|error| AppError::invalid_data("Invalid trash settings", error))
+}
+
+pub fn save_trash_settings(settings: &TrashSettings) -> Result<TrashSettings, AppError> {
+  let path = settings_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|error| AppError::io("Create config dir failed", error))?;
+  }
+
+  let raw = serde_json::to_string_pretty(settings)
+    .map_err(|error| AppError::internal("Serialize trash settings failed", error))?;
+  fs::write(&path, raw).map_err(|error| AppError::io("Write trash settings failed", error))?;
+  Ok(settings.clone())
+}
+
+// Moves the note's file into the trash; the entry id is the trash time
+// followed by the note id, so the same note can be trashed more than once.
+pub fn trash_note(id: &str) -> Result<TrashedNote, AppError> {
+  let source = notes_fs::existing_note_path(id)?;
+  let title = notes_fs::get_note(id)
+    .map(|note| note.metadata.title)
+    .unwrap_or_else(|_| id.to_string());
+
+  let dir = trash_dir()?;
+  fs::create_dir_all(&dir).map_err(|error| AppError::io("Create trash dir failed", error))?;
+
+  let now = Local::now().naive_local();
+  let mut entries = read_manifest(&dir)?;
+  let mut entry_id = format!("{}_{}", now.format(ENTRY_FORMAT), id);
+  let mut suffix = 2;
+  while entries.iter().any(|entry| entry.id == entry_id) || dir.join(&entry_id).exists() {
+    entry_id = format!("{}-{}_{}", now.format(ENTRY_FORMAT), suffix, id);
+    suffix += 1;
+  }
+
+  move_file(&source, &dir.join(&entry_id))?;
+  let entry = TrashedNote {
+    id: entry_id,
+    note_id: id.to_string(),
+    title,
+    trashed_at: dates::format_timestamp(now),
+  };
+  entries.push(entry.clone());
+  write_manifest(&dir, &entries)?;
+  Ok(entry)
+}
+
+// Newest first. Expired entries are purged before listing.
+pub fn list_trash() -> Result<Vec<TrashedNote>, AppError> {
+  purge_expired()?;
+  let mut entries = read_manifest(&trash_dir()?)?;
+  entries.reverse();
+  Ok(entries)
+}
+
+// Moves the file back under its original id, or `_2`, `_3`... when a note
+// with that name was created in the meantime.
+pub fn restore_from_trash(entry_id: &str) -> Result<NoteDetail, AppError> {
+  let dir = trash_dir()?;
+  let mut entries = read_manifest(&dir)?;
+  let position = find_entry(&entries, entry_id)?;
+  let note_id = notes_fs::available_note_id(&entries[position].note_id)?;
+  let notes_dir = notes_fs::notes_dir()?;
+
+  move_file(&dir.join(entry_id), &notes_dir.join(&note_id))?;
+  entries.remove(position);
+  write_manifest(&dir, &entries)?;
+  notes_fs::get_note(&note_id)
+}
+
+pub fn delete_from_trash(entry_id: &str) -> Result<(), AppError> {
+  let dir = trash_dir()?;
+  let mut entries = read_manifest(&dir)?;
+  let position = find_entry(&entries, entry_id)?;
+  remove_entry_file(&dir, entry_id)?;
+  entries.remove(position);
+  write_manifest(&dir, &entries)
+}
+
+// Returns the number of notes deleted for good.
+pub fn empty_trash() -> Result<usize, AppError> {
+  let dir = trash_dir()?;
+  let entries = read_manifest(&dir)?;
+  for entry in &entries {
+    remove_entry_file(&dir, &entry.id)?;
+  }
+  write_manifest(&dir, &[])?;
+  Ok(entries.len())
+}
+
+// Deletes entries older than the configured number of days; called on
+// startup and whenever the trash is listed.
+pub fn purge_expired() -> Result<usize, AppError> {
+  let settings = get_trash_settings()?;
+  if settings.purge_after_days == 0 {
+    return Ok(0);
+  }
+
+  let dir = trash_dir()?;
+  let entries = read_manifest(&dir)?;
+  let cutoff = Local::now().naive_local() - Duration::days(i64::from(settings.purge_after_days));
+  let (expired, kept): (Vec<TrashedNote>, Vec<TrashedNote>) =
+    entries.into_iter().partition(|entry| {
+      dates::parse_timestamp(&entry.trashed_at)
+        .map(|trashed_at| trashed_at < cutoff)
+        .unwrap_or(false)
+    });
+  if expired.is_empty() {
+    return Ok(0);
+  }
+
+  for entry in &expired {
+    remove_entry_file(&dir, &entry.id)?;
+  }
+  write_manifest(&dir, &kept)?;
+  Ok(expired.len())
+}
+
+fn find_entry(entries: &[TrashedNote], entry_id: &str) -> Result<usize, AppError> {
+  if entry_id.contains(['/', '\\']) || entry_id.contains("..") {
+    return Err(AppError::invalid_id("Invalid trash entry"));
+  }
+
+  entries
+    .iter()
+    .position(|entry| entry.id == entry_id)
+    .ok_or_else(|| AppError::not_found("Trash entry not found"))
+}
+
+// Entries whose file is already gone are dropped silently.
+fn remove_entry_file(dir: &Path, entry_id: &str) -> Result<(), AppError> {
+  let path = dir.join(entry_id);
+  if !path.exists() {
+    return Ok(());
+  }
+
+  fs::remove_file(&path).map_err(|error| AppError::io("Delete trashed note failed", error))
+}
+
+// The data dir may sit on another filesystem than the notes folder, where
+// rename fails; fall back to copy and remove.
+fn move_file(from: &Path, to: &Path) -> Result<(), AppError> {
+  if fs::rename(from, to).is_ok() {
+    return Ok(());
+  }
+
+  fs::copy(from, to).map_err(|error| AppError::io("Move note failed", error))?;
+  fs::remove_file(from).map_err(|error| AppError::io("Move note failed", error))
+}
+
+fn read_manifest(dir: &Path) -> Result<Vec<TrashedNote>, AppError> {
+  let path = dir.join(MANIFEST_FILE);
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+
+  let raw = fs::read_to_string(&path).map_err(|error| AppError::io("Read trash failed", error))?;
+  serde_json::from_str(&raw).map_err(|error| AppError::invalid_data("Invalid trash", error))
+}
+
+fn write_manifest(dir: &Path, entries: &[TrashedNote]) -> Result<(), AppError> {
+  let raw = serde_json::to_string_pretty(entries)
+    .map_err(|error| AppError::internal("Serialize trash failed", error))?;
+  fs::write(dir.join(MANIFEST_FILE), raw).map_err(|error| AppError::io("Write trash failed", error))
+}
+
+fn trash_dir() -> Result<PathBuf, AppError> {
+  indexer::data_dir().map(|dir| dir.join(TRASH_DIR))
+}
+
+fn settings_path() -> Result<PathBuf, AppError> {
+  notes_fs::vault_config_dir().map(|dir| dir.join(TRASH_SETTINGS_FILE))
+}
@@ -1,47 +1,37 @@
 use std::collections::HashSet;
-use std::fmt;
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use notify::event::{ModifyKind, RenameMode};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 
-use crate::services::{indexer, notes_fs};
+use crate::models::AppError;
+use crate::services::{indexer, links, notes_fs};
 
 const DEBOUNCE_MS: u64 = 300;
 const POLL_MS: u64 = 100;
 
-#[derive(Debug)]
-pub struct WatcherError {
-  details: String,
-}
-
-impl WatcherError {
-  pub fn new(details: impl Into<String>) -> Self {
-    Self {
-      details: details.into(),
-    }
-  }
-}
-
-impl fmt::Display for WatcherError {
-  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(formatter, "{}", self.details)
-  }
-}
-
-impl std::error::Error for WatcherError {}
-
 pub struct WatcherHandle {
   _watcher: RecommendedWatcher,
 }
 
-pub fn start_watching() -> Result<WatcherHandle, WatcherError> {
-  let notes_dir = notes_fs::notes_dir().map_err(|error| WatcherError::new(error.to_string()))?;
+pub fn start_watching<F>(on_change: F) -> Result<WatcherHandle, AppError>
+where
+  F: Fn() + Send + 'static,
+{
+  let notes_dir = notes_fs::notes_dir()?;
   if !notes_dir.exists() {
     if let Err(error) = std::fs::create_dir_all(&notes_dir) {
-      return Err(WatcherError::new(format!("Create notes dir failed: {}", error)));
+      return Err(AppError::io("Create notes dir failed", error));
+    }
+  }
+  // Vault settings such as saved searches are synced too.
+  let config_dir = notes_fs::vault_config_dir()?;
+  if !config_dir.exists() {
+    if let Err(error) = std::fs::create_dir_all(&config_dir) {
+      return Err(AppError::io("Create config dir failed", error));
     }
   }
 
@@ -49,29 +39,51 @@ pub fn start_watching() -> Result<WatcherHandle, WatcherError> {
   let mut watcher = notify::recommended_watcher(move |res| {
     let _ = sender.send(res);
   })
-  .map_err(|error| WatcherError::new(format!("Watcher init failed: {}", error)))?;
+  .map_err(|error| AppError::io("Watcher init failed", error))?;
 
   watcher
     .watch(&notes_dir, RecursiveMode::NonRecursive)
-    .map_err(|error| WatcherError::new(format!("Watch failed: {}", error)))?;
+    .map_err(|error| AppError::io("Watch failed", error))?;
+  watcher
+    .watch(&config_dir, RecursiveMode::NonRecursive)
+    .map_err(|error| AppError::io("Watch failed", error))?;
 
   thread::spawn(move || {
     let mut pending_upsert: HashSet<PathBuf> = HashSet::new();
     let mut pending_delete: HashSet<PathBuf> = HashSet::new();
+    let mut pending_renames: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut config_changed = false;
     let mut last_event: Option<Instant> = None;
 
     loop {
       match receiver.recv_timeout(Duration::from_millis(POLL_MS)) {
         Ok(event) => {
-          if let Ok(event) = event {
-            queue_event(event, &mut pending_upsert, &mut pending_delete);
+          if let Ok(mut event) = event {
+            // Settings files are not notes: they only trigger a refresh.
+            let before = event.paths.len();
+            event.paths.retain(|path| !path.starts_with(&config_dir));
+            config_changed |= event.paths.len() != before;
+            queue_event(
+              event,
+              &mut pending_upsert,
+              &mut pending_delete,
+              &mut pending_renames,
+            );
             last_event = Some(Instant::now());
           }
         }
         Err(mpsc::RecvTimeoutError::Timeout) => {
           if let Some(last) = last_event {
             if last.elapsed() >= Duration::from_millis(DEBOUNCE_MS) {
-              flush_pending(&mut pending_upsert, &mut pending_delete);
+              let notes_changed = flush_pending(
+                &mut pending_upsert,
+                &mut pending_delete,
+                &mut pending_renames,
+              );
+              if notes_changed || config_changed {
+                on_change();
+              }
+              config_changed = false;
               last_event = None;
             }
           }
@@ -80,18 +92,62 @@ pub fn start_watching() -> Result<WatcherHandle, WatcherError> {
       }
     }
 
-    flush_pending(&mut pending_upsert, &mut pending_delete);
+    flush_pending(
+      &mut pending_upsert,
+      &mut pending_delete,
+      &mut pending_renames,
+    );
   });
 
   Ok(WatcherHandle { _watcher: watcher })
 }
 
 fn queue_event(
-  event: Event,
+  mut event: Event,
   pending_upsert: &mut HashSet<PathBuf>,
   pending_delete: &mut HashSet<PathBuf>,
+  pending_renames: &mut Vec<(PathBuf, PathBuf)>,
 ) {
+  // Syncthing versioning moves replaced or deleted notes into .stversions:
+  // seen from the notes folder that is a delete, and a move back a create.
+  if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = event.kind {
+    if event.paths.len() == 2 {
+      let from_ignored = notes_fs::is_ignored_path(&event.paths[0]);
+      let to_ignored = notes_fs::is_ignored_path(&event.paths[1]);
+      if to_ignored && !from_ignored {
+        pending_upsert.remove(&event.paths[0]);
+        pending_delete.insert(event.paths[0].clone());
+        return;
+      }
+      if from_ignored && !to_ignored {
+        pending_delete.remove(&event.paths[1]);
+        pending_upsert.insert(event.paths[1].clone());
+        return;
+      }
+    }
+  }
+
+  event.paths.retain(|path| !notes_fs::is_ignored_path(path));
+  if event.paths.is_empty() {
+    return;
+  }
+
   match event.kind {
+    EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+      let from = event.paths[0].clone();
+      let to = event.paths[1].clone();
+      pending_upsert.remove(&from);
+      pending_delete.insert(from.clone());
+      pending_delete.remove(&to);
+      pending_upsert.insert(to.clone());
+      pending_renames.push((from, to));
+    }
+    EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+      for path in event.paths {
+        pending_upsert.remove(&path);
+        pending_delete.insert(path);
+      }
+    }
     EventKind::Create(_) | EventKind::Modify(_) => {
       for path in event.paths {
         pending_delete.remove(&path);
@@ -108,12 +164,33 @@ fn queue_event(
   }
 }
 
-fn flush_pending(pending_upsert: &mut HashSet<PathBuf>, pending_delete: &mut HashSet<PathBuf>) {
+// Renamed files are upserted before their old entry is deleted so links to
+// the old file name can be rewritten while both titles are still indexed.
+fn flush_pending(
+  pending_upsert: &mut HashSet<PathBuf>,
+  pending_delete: &mut HashSet<PathBuf>,
+  pending_renames: &mut Vec<(PathBuf, PathBuf)>,
+) -> bool {
+  let changed = !pending_upsert.is_empty() || !pending_delete.is_empty();
+
   for path in pending_upsert.drain() {
     let _ = indexer::upsert_note_by_path(&path);
   }
 
+  for (from, to) in pending_renames.drain(..) {
+    if let (Some(old_id), Some(new_id)) = (
+      notes_fs::note_id_from_path(&from),
+      notes_fs::note_id_from_path(&to),
+    ) {
+      if let Err(error) = links::relink_renamed_note(&old_id, &new_id) {
+        eprintln!("Relink failed: {}", error);
+      }
+    }
+  }
+
   for path in pending_delete.drain() {
     let _ = indexer::delete_note_by_path(&path);
   }
+
+  changed
 }